@@ -118,6 +118,17 @@ export default function messageListener(packet: ToClientPacket){
             
         break;
         case "rejectStart":
+            if (typeof packet.reason === "object") {
+                const error = packet.reason.roleListGenerationFailed.error;
+                const value = error.type === "outlineHasNoRoles" ? error.outline + 1
+                    : error.type === "constraintCannotBeMet" ? error.constraint + 1
+                    : "";
+                ANCHOR_CONTROLLER?.pushErrorCard({ 
+                    title: translate("notification.rejectStart"), 
+                    body: translate("notification.rejectStart.roleListGenerationFailed." + error.type, value) 
+                });
+                break;
+            }
             switch(packet.reason) {
                 case "gameEndsInstantly":
                     ANCHOR_CONTROLLER?.pushErrorCard({ title: translate("notification.rejectStart"), body: translate("notification.rejectStart.gameEndsInstantly") });
@@ -125,9 +136,6 @@ export default function messageListener(packet: ToClientPacket){
                 case "roleListTooSmall":
                     ANCHOR_CONTROLLER?.pushErrorCard({ title: translate("notification.rejectStart"), body: translate("notification.rejectStart.roleListTooSmall") });
                 break;
-                case "roleListCannotCreateRoles":
-                    ANCHOR_CONTROLLER?.pushErrorCard({ title: translate("notification.rejectStart"), body: translate("notification.rejectStart.roleListCannotCreateRoles") });
-                break;
                 case "zeroTimeGame":
                     ANCHOR_CONTROLLER?.pushErrorCard({ title: translate("notification.rejectStart"), body: translate("notification.rejectStart.zeroTimeGame") });
                 break;
@@ -14,6 +14,22 @@ export type LobbyPreviewData = {
     players: [LobbyClientID, string][]
 }
 
+export type RejectStartReason = "gameEndsInstantly" | "roleListTooSmall" | "zeroTimeGame" | "playerDisconnected" | {
+    roleListGenerationFailed: { error: RoleListGenerationError }
+}
+
+export type RoleListGenerationError = {
+    type: "outlineHasNoRoles",
+    outline: number
+} | {
+    type: "maximumCountExceeded"
+} | {
+    type: "constraintCannotBeMet",
+    constraint: number
+} | {
+    type: "tooComplex"
+}
+
 export type ToClientPacket = {
     type: "pong",
 } | {
@@ -53,7 +69,7 @@ export type ToClientPacket = {
     fellowInsiders: PlayerIndex[]
 } | {
     type: "rejectStart",
-    reason: string
+    reason: RejectStartReason
 } | {
     type: "playersHost",
     hosts: LobbyClientID[],
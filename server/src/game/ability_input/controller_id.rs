@@ -21,6 +21,9 @@ pub enum ControllerID{
     PitchforkVote{
         player: PlayerReference
     },
+    EliminationVote{
+        player: PlayerReference
+    },
     SyndicateGunItemShoot,
     SyndicateGunItemGive,
     SyndicateChooseBackup,
@@ -41,6 +44,9 @@ impl ControllerID{
     pub fn pitchfork_vote(player: PlayerReference)->Self{
         Self::PitchforkVote{player}
     }
+    pub fn elimination_vote(player: PlayerReference)->Self{
+        Self::EliminationVote{player}
+    }
     pub fn syndicate_gun_item_shoot()->Self{
         Self::SyndicateGunItemShoot
     }
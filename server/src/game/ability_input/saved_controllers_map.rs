@@ -9,7 +9,7 @@ use crate::{
             on_controller_selection_changed::OnControllerSelectionChanged,
             on_validated_ability_input_received::OnValidatedAbilityInputReceived
         }, 
-        phase::PhaseType, player::PlayerReference, Game
+        modifiers::Modifiers, phase::PhaseType, player::PlayerReference, Game
     }, packet::ToClientPacket, vec_map::VecMap, vec_set::VecSet
 };
 
@@ -87,6 +87,9 @@ impl SavedControllersMap{
         new_controller_parameters_map.combine_overwrite(
             Pitchfork::controller_parameters_map(game)
         );
+        new_controller_parameters_map.combine_overwrite(
+            Modifiers::controller_parameters_map(game)
+        );
 
         let current_controller_parameters = &game.saved_controllers.controller_parameters();
 
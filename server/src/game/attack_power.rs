@@ -1,11 +1,15 @@
+use serde::Serialize;
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub enum AttackPower {
     Basic = 1,
     ArmorPiercing = 2,
     ProtectionPiercing = 3
 }
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub enum DefensePower {
     None = 0,
     Armor = 1,
@@ -0,0 +1,82 @@
+use serde::{Deserialize, Serialize};
+
+/// Host-chosen words that are masked or blocked in lobby and game chat.
+/// Words only match whole words, ignoring case, so "class" isn't caught by "ass".
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ChatFilter{
+    pub mode: ChatFilterMode,
+    pub words: Vec<String>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ChatFilterMode{
+    #[default]
+    Off,
+    /// Filtered words are replaced with asterisks
+    Mask,
+    /// Messages with filtered words aren't sent
+    Block,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ChatFilterError{
+    TooManyWords,
+    /// Words must be a single word of letters and numbers
+    InvalidWord{word: u8},
+}
+
+impl ChatFilter{
+    pub const MAX_WORDS: usize = 200;
+    pub const MAX_WORD_LENGTH: usize = 30;
+
+    pub fn check(&self)->Result<(), ChatFilterError>{
+        if self.words.len() > Self::MAX_WORDS {
+            return Err(ChatFilterError::TooManyWords);
+        }
+        for (index, word) in self.words.iter().enumerate() {
+            if
+                word.is_empty() ||
+                word.chars().count() > Self::MAX_WORD_LENGTH ||
+                !word.chars().all(char::is_alphanumeric)
+            {
+                return Err(ChatFilterError::InvalidWord { word: index as u8 });
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the text to send, or None if the message is blocked
+    pub fn apply(&self, text: &str)->Option<String>{
+        if self.mode == ChatFilterMode::Off || self.words.is_empty() {
+            return Some(text.to_string());
+        }
+
+        let mut out = String::with_capacity(text.len());
+        let mut word = String::new();
+        for character in text.chars().chain(std::iter::once(' ')) {
+            if character.is_alphanumeric() {
+                word.push(character);
+                continue;
+            }
+            if self.is_filtered(&word) {
+                if self.mode == ChatFilterMode::Block {
+                    return None;
+                }
+                out.extend(word.chars().map(|_| '*'));
+            } else {
+                out.push_str(&word);
+            }
+            word.clear();
+            out.push(character);
+        }
+        out.pop();
+        Some(out)
+    }
+
+    fn is_filtered(&self, word: &str)->bool{
+        !word.is_empty() && self.words.iter().any(|filtered| filtered.to_lowercase() == word.to_lowercase())
+    }
+}
@@ -10,12 +10,17 @@ pub enum ChatGroup {
 
     Mafia,
     Cult,
+    /// Chat for the host's custom insider group with this index
+    Custom(u8),
 
     Jail,
     Kidnapped,
     Interview,
     Puppeteer,
     Warden,
+
+    /// Only spectators can see or send these
+    Spectator,
 }
 impl ChatGroup{
     pub fn player_receive_from_chat_group(&self, game: &Game, player_ref: PlayerReference)->bool{
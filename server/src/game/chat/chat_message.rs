@@ -1,24 +1,45 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
 use serde::Serialize;
 
+use crate::game::player::PlayerIndex;
+
 use super::{chat_group::ChatGroup, chat_message_variant::ChatMessageVariant};
 
+/// Counts up from 0 in each game, so clients can order messages and skip ones they already have.
+/// Everyone who receives the same message gets the same ID.
+pub type ChatMessageID = u32;
+
 #[derive(Clone, Debug, Serialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct ChatMessage{
+    pub id: ChatMessageID,
+    /// Milliseconds since the unix epoch when the server created the message
+    pub time: u64,
     pub variant: ChatMessageVariant,
     pub chat_group: Option<ChatGroup>,
+    /// Set on the copy sent to a player the message mentions, so their client can notify them
+    pub notify: bool,
 }
 impl ChatMessage{
-    pub fn new(variant: ChatMessageVariant, chat_group: Option<ChatGroup>)->Self{
-        Self{variant,chat_group}
+    pub fn new(id: ChatMessageID, variant: ChatMessageVariant, chat_group: Option<ChatGroup>)->Self{
+        let time = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |time| time.as_millis() as u64);
+        Self{id, time, variant, chat_group, notify: false}
     }
-    pub fn new_private(variant: ChatMessageVariant)->Self{
-        Self{variant, chat_group: None}
+    pub fn new_private(id: ChatMessageID, variant: ChatMessageVariant)->Self{
+        Self::new(id, variant, None)
     }
-    pub fn new_non_private(variant: ChatMessageVariant, chat_group: ChatGroup)->Self{
-        Self{variant, chat_group: Some(chat_group)}
+    pub fn new_non_private(id: ChatMessageID, variant: ChatMessageVariant, chat_group: ChatGroup)->Self{
+        Self::new(id, variant, Some(chat_group))
     }
     pub fn get_variant(&self)->&ChatMessageVariant{
         &self.variant
     }
-}
\ No newline at end of file
+    pub fn mentions(&self, player: PlayerIndex)->bool{
+        matches!(&self.variant, ChatMessageVariant::Normal { mentions, .. } if mentions.contains(&player))
+    }
+    /// The copy of this message that the player receives
+    pub fn for_player(&self, player: PlayerIndex)->Self{
+        Self{notify: self.mentions(player), ..self.clone()}
+    }
+}
@@ -1,9 +1,9 @@
 use serde::{Deserialize, Serialize};
 
 use crate::game::{
-    ability_input::*, components::synopsis::Synopsis, grave::Grave, phase::PhaseState, player::{PlayerIndex, PlayerReference}, role::{
+    ability_input::*, chat::ChatMessageID, components::synopsis::Synopsis, grave::Grave, phase::PhaseState, player::{PlayerIndex, PlayerReference}, role::{
         auditor::AuditorResult, engineer::TrapState, kira::KiraResult, krampus::KrampusAbility, santa_claus::SantaListKind, spy::SpyBug, Role
-    }, role_list::RoleOutline, tag::Tag, verdict::Verdict, win_condition::WinCondition
+    }, role_list::RoleOutline, spectator::spectator_pointer::SpectatorID, tag::Tag, verdict::Verdict, win_condition::WinCondition
 };
 
 
@@ -15,6 +15,7 @@ pub enum MessageSender {
     Jailor,
     Reporter,
     LivingToDead{player: PlayerIndex},
+    Spectator{spectator: SpectatorID},
 }
 
 // Determines message color
@@ -32,6 +33,12 @@ pub enum ChatMessageVariant {
         message_sender: MessageSender, 
         text: String,
         block: bool,
+        /// A message the sender could see when they replied to it
+        #[serde(default)]
+        reply_to: Option<ChatMessageID>,
+        /// Players mentioned by the sender, each one a real player and only listed once
+        #[serde(default)]
+        mentions: Vec<PlayerIndex>,
     },
 
     #[serde(rename_all = "camelCase")]
@@ -93,6 +100,25 @@ pub enum ChatMessageVariant {
         innocent: u8, 
         guilty: u8 
     },
+    #[serde(rename_all = "camelCase")]
+    NominationTieBroken {
+        tied_players: Vec<PlayerIndex>,
+        player_index: PlayerIndex
+    },
+    #[serde(rename_all = "camelCase")]
+    NominationRevote {
+        tied_players: Vec<PlayerIndex>
+    },
+    #[serde(rename_all = "camelCase")]
+    EliminationRunoff {
+        tied_players: Vec<PlayerIndex>
+    },
+    #[serde(rename_all = "camelCase")]
+    EliminationResult {
+        eliminated: Option<PlayerIndex>,
+        tied_players: Vec<PlayerIndex>,
+        votes: u8
+    },
     
     /* Misc */
     #[serde(rename_all = "camelCase")]
@@ -109,6 +135,10 @@ pub enum ChatMessageVariant {
     #[serde(rename_all = "camelCase")]
     MayorRevealed{player_index: PlayerIndex},
     InvalidWhisper,
+    /// Sent to a player whose message had a word from the host's chat filter
+    BlockedByChatFilter,
+    #[serde(rename_all = "camelCase")]
+    PlayerMuted{player_index: PlayerIndex, muted: bool},
     #[serde(rename_all = "camelCase")]
     PoliticianCountdownStarted,
     #[serde(rename_all = "camelCase")]
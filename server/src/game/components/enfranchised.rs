@@ -0,0 +1,37 @@
+use crate::{game::{player::PlayerReference, tag::Tag, vote_weight::VoteType, Game}, vec_set::VecSet};
+
+/// Players who publicly hold extra voting power, like a revealed Mayor.
+/// Everyone sees the Enfranchised tag on these players.
+#[derive(Default, Clone)]
+pub struct Enfranchised{
+    players: VecSet<PlayerReference>
+}
+impl Enfranchised{
+    const EXTRA_VOTE_WEIGHT: u8 = 2;
+
+    pub fn enfranchise(game: &mut Game, player: PlayerReference){
+        if game.enfranchised.players.insert(player).is_some() {return}
+
+        for viewer in PlayerReference::all_players(game){
+            viewer.push_player_tag(game, player, Tag::Enfranchised);
+        }
+    }
+    pub fn unenfranchise(game: &mut Game, player: PlayerReference){
+        if game.enfranchised.players.remove(&player).is_none() {return}
+
+        for viewer in PlayerReference::all_players(game){
+            viewer.remove_player_tag(game, player, Tag::Enfranchised);
+        }
+    }
+    pub fn is_enfranchised(game: &Game, player: PlayerReference)->bool{
+        game.enfranchised.players.contains(&player)
+    }
+
+    pub fn vote_weight(game: &Game, voter: PlayerReference, _vote_type: VoteType, weight: u8)->u8{
+        if Self::is_enfranchised(game, voter) {
+            weight.saturating_add(Self::EXTRA_VOTE_WEIGHT)
+        }else{
+            weight
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use serde::Serialize;
+
+use crate::game::{
+    game_conclusion::GameConclusion, grave::Grave, phase::PhaseType, player::PlayerReference,
+    role::Role, verdict::Verdict, win_condition::WinCondition, Game
+};
+
+/// A timeline of everything that happened, for reviews and stats sites.
+/// Sent to everyone when the game is over, and can be exported as JSON.
+/// Unlike what players saw, graves here keep the real role even if they were obscured.
+#[derive(Default, Clone)]
+pub struct GameHistory{
+    events: Vec<GameHistoryEvent>
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameHistoryEvent{
+    day_number: u8,
+    phase: PhaseType,
+    #[serde(flatten)]
+    kind: GameHistoryEventKind,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(tag = "type")]
+pub enum GameHistoryEventKind{
+    #[serde(rename_all = "camelCase")]
+    Grave{
+        /// The grave as players saw it
+        shown: Grave,
+        /// The grave before anything changed it when it was added, like the obscured graves modifier or a Mortician
+        original: Grave,
+        /// The player's actual role when they died
+        role: Role,
+    },
+    RoleSwitch{player: PlayerReference, old: Role, new: Role},
+    Convert{player: PlayerReference, old: WinCondition, new: WinCondition},
+    #[serde(rename_all = "camelCase")]
+    TrialResult{
+        player_on_trial: PlayerReference,
+        guilty: u8,
+        innocent: u8,
+        /// Every living voter's verdict, even with a secret ballot
+        verdicts: Vec<(PlayerReference, Verdict)>,
+        /// Whether the trial went on to final words
+        voted_guilty: bool,
+    },
+    GameOver{conclusion: GameConclusion},
+}
+
+/// What gets exported for stats sites
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameHistoryExport{
+    players: Vec<GameHistoryPlayer>,
+    events: Vec<GameHistoryEvent>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameHistoryPlayer{
+    name: String,
+    role: Role,
+    alive: bool,
+    won: bool,
+}
+
+impl GameHistoryEvent{
+    pub fn day_number(&self)->u8{
+        self.day_number
+    }
+    pub fn kind(&self)->&GameHistoryEventKind{
+        &self.kind
+    }
+}
+
+impl GameHistory{
+    pub fn record(game: &mut Game, kind: GameHistoryEventKind){
+        let event = GameHistoryEvent {
+            day_number: game.day_number(),
+            phase: game.current_phase().phase(),
+            kind
+        };
+        game.game_history.events.push(event);
+    }
+    pub fn on_role_switch(game: &mut Game, player: PlayerReference, old: Role, new: Role){
+        if old == new {return}
+        Self::record(game, GameHistoryEventKind::RoleSwitch { player, old, new });
+    }
+    pub fn on_convert(game: &mut Game, player: PlayerReference, old: WinCondition, new: WinCondition){
+        if old == new {return}
+        Self::record(game, GameHistoryEventKind::Convert { player, old, new });
+    }
+    pub fn timeline(game: &Game)->&Vec<GameHistoryEvent>{
+        &game.game_history.events
+    }
+    pub fn export(game: &Game)->GameHistoryExport{
+        GameHistoryExport {
+            players: PlayerReference::all_players(game)
+                .map(|player| GameHistoryPlayer {
+                    name: player.name(game).clone(),
+                    role: player.role(game),
+                    alive: player.alive(game),
+                    won: player.get_won_game(game),
+                })
+                .collect(),
+            events: Self::timeline(game).clone(),
+        }
+    }
+    /// For sending the game to a stats site when it's over
+    pub fn to_json_string(game: &Game)->Result<String, serde_json::Error>{
+        serde_json::to_string(&Self::export(game))
+    }
+}
@@ -1,18 +1,25 @@
 use serde::{Deserialize, Serialize};
 
-use crate::{game::{chat::{ChatGroup, ChatMessageVariant}, player::PlayerReference, Game}, packet::ToClientPacket, vec_set::VecSet};
+use crate::{game::{chat::{ChatGroup, ChatMessageVariant}, player::PlayerReference, settings::Settings, Game}, packet::ToClientPacket, vec_set::VecSet};
 
 #[derive(Default)]
 pub struct InsiderGroups{
     mafia: InsiderGroup,
     cult: InsiderGroup,
-    puppeteer: InsiderGroup
+    puppeteer: InsiderGroup,
+    custom: Vec<InsiderGroup>
 }
 impl InsiderGroups{
+    pub fn new(settings: &Settings)->Self{
+        Self{
+            custom: settings.custom_insider_groups.iter().map(|_| InsiderGroup::default()).collect(),
+            ..Default::default()
+        }
+    }
     pub fn on_remove_role_label(game: &mut Game, player: PlayerReference, concealed_player: PlayerReference){
-        InsiderGroupID::Mafia.on_remove_role_label(game, player, concealed_player);
-        InsiderGroupID::Cult.on_remove_role_label(game, player, concealed_player);
-        InsiderGroupID::Puppeteer.on_remove_role_label(game, player, concealed_player);
+        for group in InsiderGroupID::all(game){
+            group.on_remove_role_label(game, player, concealed_player);
+        }
     }
 }
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
@@ -20,7 +27,32 @@ impl InsiderGroups{
 pub enum InsiderGroupID{
     Mafia,
     Cult,
-    Puppeteer
+    Puppeteer,
+    /// Index into the host's custom insider groups
+    Custom(u8)
+}
+
+/// An insider group made by the host, like a Masons style town team or a second mafia.
+/// Its members see each other's roles and get their own chat.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomInsiderGroup {
+    pub name: String,
+}
+impl CustomInsiderGroup {
+    pub const MAX_NAME_LENGTH: usize = 30;
+    pub const MAX_COUNT: usize = 8;
+}
+
+/// Why the host's custom insider groups weren't accepted
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum CustomInsiderGroupError {
+    TooManyGroups,
+    InvalidName{group: u8},
+    DuplicateName{group: u8},
+    /// Role list options use this group, so it can't be removed or moved
+    GroupInUse{group: u8},
 }
 #[derive(Default)]
 pub struct InsiderGroup{
@@ -43,68 +75,79 @@ impl<'a> From<&'a mut InsiderGroup> for &'a mut VecSet<PlayerReference> {
 }
 
 impl InsiderGroupID{
-    //const
-    pub fn all()->VecSet<InsiderGroupID>{
+    /// Every group in this game, including the host's custom groups
+    pub fn all(game: &Game)->VecSet<InsiderGroupID>{
         vec![
             InsiderGroupID::Mafia,
             InsiderGroupID::Cult,
             InsiderGroupID::Puppeteer
-        ].into_iter().collect()
+        ].into_iter()
+            .chain((0..game.revealed_groups.custom.len() as u8).map(InsiderGroupID::Custom))
+            .collect()
     }
     pub const fn get_insider_chat_group(&self)->ChatGroup{
         match self{
             InsiderGroupID::Mafia=>ChatGroup::Mafia,
             InsiderGroupID::Cult=>ChatGroup::Cult,
-            InsiderGroupID::Puppeteer=>ChatGroup::Puppeteer
+            InsiderGroupID::Puppeteer=>ChatGroup::Puppeteer,
+            InsiderGroupID::Custom(id)=>ChatGroup::Custom(*id)
         }
     }
-    pub fn get_insider_group_from_chat_group(chat: &ChatGroup)->Option<InsiderGroupID>{
-        for inside in Self::all() {
-            if inside.get_insider_chat_group() == *chat {
-                return Some(inside)
-            }
+    pub const fn get_insider_group_from_chat_group(chat: &ChatGroup)->Option<InsiderGroupID>{
+        match chat{
+            ChatGroup::Mafia=>Some(InsiderGroupID::Mafia),
+            ChatGroup::Cult=>Some(InsiderGroupID::Cult),
+            ChatGroup::Puppeteer=>Some(InsiderGroupID::Puppeteer),
+            ChatGroup::Custom(id)=>Some(InsiderGroupID::Custom(*id)),
+            _=>None
         }
-        None
     }
-    fn revealed_group<'a>(&self, game: &'a Game)->&'a InsiderGroup{
+    /// None for custom groups that the host didn't make
+    fn revealed_group<'a>(&self, game: &'a Game)->Option<&'a InsiderGroup>{
         match self{
-            InsiderGroupID::Mafia=>&game.revealed_groups.mafia,
-            InsiderGroupID::Cult=>&game.revealed_groups.cult,
-            InsiderGroupID::Puppeteer=>&game.revealed_groups.puppeteer
+            InsiderGroupID::Mafia=>Some(&game.revealed_groups.mafia),
+            InsiderGroupID::Cult=>Some(&game.revealed_groups.cult),
+            InsiderGroupID::Puppeteer=>Some(&game.revealed_groups.puppeteer),
+            InsiderGroupID::Custom(id)=>game.revealed_groups.custom.get(*id as usize)
         }
     }
-    fn revealed_group_mut<'a>(&self, game: &'a mut Game)->&'a mut InsiderGroup{
+    fn revealed_group_mut<'a>(&self, game: &'a mut Game)->Option<&'a mut InsiderGroup>{
         match self{
-            InsiderGroupID::Mafia=>&mut game.revealed_groups.mafia,
-            InsiderGroupID::Cult=>&mut game.revealed_groups.cult,
-            InsiderGroupID::Puppeteer=>&mut game.revealed_groups.puppeteer
+            InsiderGroupID::Mafia=>Some(&mut game.revealed_groups.mafia),
+            InsiderGroupID::Cult=>Some(&mut game.revealed_groups.cult),
+            InsiderGroupID::Puppeteer=>Some(&mut game.revealed_groups.puppeteer),
+            InsiderGroupID::Custom(id)=>game.revealed_groups.custom.get_mut(*id as usize)
         }
     }
     pub fn players<'a>(&self, game: &'a Game)->&'a VecSet<PlayerReference>{
-        self.revealed_group(game).into()
+        static NO_PLAYERS: VecSet<PlayerReference> = VecSet::new();
+        self.revealed_group(game).map_or(&NO_PLAYERS, Into::into)
     }
 
     // Mutations
     pub unsafe fn add_player_to_revealed_group_unchecked(&self, game: &mut Game, player: PlayerReference){
-        let players: &mut VecSet<PlayerReference> = self.revealed_group_mut(game).into();
+        let Some(group) = self.revealed_group_mut(game) else {return};
+        let players: &mut VecSet<PlayerReference> = group.into();
         players.insert(player);
     }
     pub fn add_player_to_revealed_group(&self, game: &mut Game, player: PlayerReference){
-        let players: &mut VecSet<PlayerReference> = self.revealed_group_mut(game).into();
+        let Some(group) = self.revealed_group_mut(game) else {return};
+        let players: &mut VecSet<PlayerReference> = group.into();
         if players.insert(player).is_none() {
             self.reveal_group_players(game);
         }
         Self::send_player_insider_groups(game, player);
     }
     pub fn remove_player_from_revealed_group(&self, game: &mut Game, player: PlayerReference){
-        let players: &mut VecSet<PlayerReference> = self.revealed_group_mut(game).into();
+        let Some(group) = self.revealed_group_mut(game) else {return};
+        let players: &mut VecSet<PlayerReference> = group.into();
         if players.remove(&player).is_some() {
             self.reveal_group_players(game);
         }
         Self::send_player_insider_groups(game, player);
     }
     pub fn set_player_revealed_groups(set: VecSet<InsiderGroupID>, game: &mut Game, player: PlayerReference){
-        for group in InsiderGroupID::all(){
+        for group in InsiderGroupID::all(game){
             if set.contains(&group){
                 group.add_player_to_revealed_group(game, player);
             }else{
@@ -113,10 +156,9 @@ impl InsiderGroupID{
         }
     }
     pub fn start_game_set_player_revealed_groups(set: VecSet<InsiderGroupID>, game: &mut Game, player: PlayerReference){
-        for group in InsiderGroupID::all(){
+        for group in InsiderGroupID::all(game){
             if set.contains(&group){
-                let players: &mut VecSet<PlayerReference> = group.revealed_group_mut(game).into();
-                players.insert(player);
+                unsafe { group.add_player_to_revealed_group_unchecked(game, player); }
             }
         }
         Self::send_player_insider_groups(game, player);
@@ -125,7 +167,7 @@ impl InsiderGroupID{
     // non related mutations
     pub fn send_player_insider_groups(game: &Game, player: PlayerReference){
         let mut groups = VecSet::new();
-        for group in InsiderGroupID::all(){
+        for group in InsiderGroupID::all(game){
             if group.is_player_in_revealed_group(game, player){
                 groups.insert(group);
             }
@@ -133,8 +175,7 @@ impl InsiderGroupID{
         player.send_packet(game, ToClientPacket::YourInsiderGroups{insider_groups: groups});
     }
     pub fn reveal_group_players(&self, game: &mut Game){
-        let players: VecSet<PlayerReference> = <&InsiderGroup as Into<&VecSet<PlayerReference>>>::
-            into(self.revealed_group(game)).clone();
+        let players = self.players(game).clone();
 
         for a in players.clone() {
             Self::send_fellow_insiders(game, a);
@@ -146,9 +187,7 @@ impl InsiderGroupID{
 
     // Events
     pub fn on_remove_role_label(&self, game: &mut Game, player: PlayerReference, concealed_player: PlayerReference){
-        
-        let players: &VecSet<PlayerReference> = self.revealed_group(game).into();
-        if players.contains(&concealed_player) && players.contains(&player) {
+        if self.players_both_in_revealed_group(game, player, concealed_player) {
             self.reveal_group_players(game);
         }
     }
@@ -156,19 +195,18 @@ impl InsiderGroupID{
 
     // Queries
     pub fn is_player_in_revealed_group(&self, game: &Game, player: PlayerReference)->bool{
-        let players: &VecSet<PlayerReference> = self.revealed_group(game).into();
-        players.contains(&player)
+        self.players(game).contains(&player)
     }
     pub fn players_both_in_revealed_group(&self, game: &Game, a: PlayerReference, b: PlayerReference)->bool{
-        let players: &VecSet<PlayerReference> = self.revealed_group(game).into();
+        let players = self.players(game);
         players.contains(&a) && players.contains(&b)
     }
     pub fn in_same_revealed_group(game: &Game, a: PlayerReference, b: PlayerReference)->bool{
-        InsiderGroupID::all().iter().any(|group| group.players_both_in_revealed_group(game, a, b))
+        InsiderGroupID::all(game).iter().any(|group| group.players_both_in_revealed_group(game, a, b))
     }
     pub fn all_players_in_same_revealed_group_with_actor(game: &Game, actor_ref: PlayerReference)->VecSet<PlayerReference>{
         let mut players = VecSet::new();
-        for group in InsiderGroupID::all(){
+        for group in InsiderGroupID::all(game){
             if group.is_player_in_revealed_group(game, actor_ref){
                 players.extend(group.players(game).clone());
             }
@@ -176,7 +214,7 @@ impl InsiderGroupID{
         players
     }
     pub fn all_insider_groups_with_player(game: &Game, player_ref: PlayerReference)->VecSet<InsiderGroupID>{
-        InsiderGroupID::all()
+        InsiderGroupID::all(game)
             .into_iter()
             .filter(|group| 
                 group.is_player_in_revealed_group(game, player_ref)
@@ -11,8 +11,14 @@ pub mod insider_group;
 pub mod detained;
 pub mod confused;
 pub mod drunk_aura;
+pub mod enfranchised;
+pub mod muted;
+pub mod nomination_ties;
 pub mod forfeit_vote;
 pub mod night_visits;
+pub mod night_resolution_trace;
+pub mod game_history;
+pub mod will_history;
 pub mod syndicate_gun_item;
 pub mod synopsis;
 pub mod dead_can_still_play_message;
\ No newline at end of file
@@ -0,0 +1,26 @@
+use crate::{game::{chat::{ChatGroup, ChatMessageVariant}, player::PlayerReference, Game}, packet::ToClientPacket, vec_set::VecSet};
+
+/// Players the host has muted. They can't talk in the all chat, which also stops them whispering.
+#[derive(Default, Clone)]
+pub struct Muted{
+    players: VecSet<PlayerReference>
+}
+impl Muted{
+    pub fn set_muted(game: &mut Game, player: PlayerReference, muted: bool){
+        let changed = if muted {
+            game.muted.players.insert(player).is_none()
+        } else {
+            game.muted.players.remove(&player).is_some()
+        };
+        if !changed {return}
+
+        game.add_message_to_chat_group(ChatGroup::All, ChatMessageVariant::PlayerMuted { player_index: player.index(), muted });
+
+        player.send_packet(game, ToClientPacket::YourSendChatGroups { send_chat_groups:
+            player.get_current_send_chat_groups(game).into_iter().collect()
+        });
+    }
+    pub fn is_muted(game: &Game, player: PlayerReference)->bool{
+        game.muted.players.contains(&player)
+    }
+}
@@ -0,0 +1,100 @@
+use serde::Serialize;
+
+use crate::game::{
+    attack_power::{AttackPower, DefensePower}, components::night_visits::NightVisits, grave::GraveKiller,
+    player::PlayerReference, visit::Visit, Game
+};
+
+/// A record of how every night was resolved, sent to everyone when the game is over
+#[derive(Default, Clone)]
+pub struct NightResolutionTrace{
+    nights: Vec<NightTrace>
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NightTrace{
+    day_number: u8,
+    /// Visits as chosen by the players, before anything redirected them
+    chosen_visits: Vec<Visit>,
+    /// Visits at the end of the night, after roleblocks, possessions and transports
+    final_visits: Vec<Visit>,
+    events: Vec<NightTraceEvent>
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[serde(tag = "type")]
+pub enum NightTraceEvent{
+    Roleblocked{player: PlayerReference, immune: bool},
+    Wardblocked{warded: PlayerReference, visitor: PlayerReference},
+    #[serde(rename_all = "camelCase")]
+    Possessed{possessor: PlayerReference, target: PlayerReference, possessed_into: PlayerReference, immune: bool},
+    Transported{transporter: PlayerReference, first: PlayerReference, second: PlayerReference},
+    Warped{warper: PlayerReference, target: PlayerReference, destination: PlayerReference},
+    #[serde(rename_all = "camelCase")]
+    Attacked{
+        target: PlayerReference,
+        attackers: Vec<PlayerReference>,
+        grave_killer: GraveKiller,
+        attack: AttackPower,
+        defense: DefensePower,
+        outcome: AttackOutcome
+    },
+}
+
+#[derive(Clone, Copy, Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum AttackOutcome{
+    /// The target's defense was at least as strong as the attack
+    Blocked,
+    Killed,
+    /// The attack went through, but the target was already dead
+    AlreadyDead,
+}
+
+impl NightTrace{
+    pub fn day_number(&self)->u8{
+        self.day_number
+    }
+    pub fn events(&self)->&Vec<NightTraceEvent>{
+        &self.events
+    }
+}
+
+impl NightResolutionTrace{
+    /// Called after every player's selections are converted into visits
+    pub fn on_night_resolution_start(game: &mut Game){
+        let day_number = game.day_number();
+        let chosen_visits = Self::all_visits(game);
+        game.night_resolution_trace.nights.push(NightTrace{
+            day_number,
+            chosen_visits,
+            final_visits: Vec::new(),
+            events: Vec::new()
+        });
+    }
+    /// Called after every priority has been resolved
+    pub fn on_night_resolution_end(game: &mut Game){
+        let final_visits = Self::all_visits(game);
+        if let Some(night) = game.night_resolution_trace.nights.last_mut() {
+            night.final_visits = final_visits;
+        }
+    }
+    pub fn record(game: &mut Game, event: NightTraceEvent){
+        if let Some(night) = game.night_resolution_trace.nights.last_mut() {
+            night.events.push(event);
+        }
+    }
+    pub fn nights(game: &Game)->&Vec<NightTrace>{
+        &game.night_resolution_trace.nights
+    }
+    /// For exporting the trace when a night's outcome is disputed
+    pub fn to_json_string(game: &Game)->Result<String, serde_json::Error>{
+        serde_json::to_string(Self::nights(game))
+    }
+
+    fn all_visits(game: &Game)->Vec<Visit>{
+        NightVisits::all_visits(game).into_iter().copied().collect()
+    }
+}
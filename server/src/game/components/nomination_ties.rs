@@ -0,0 +1,118 @@
+use rand::seq::IteratorRandom;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    game::{
+        chat::{ChatGroup, ChatMessageVariant},
+        phase::{PhaseState, PhaseStateMachine, PhaseType},
+        player::PlayerReference, vote_weight::VoteWeight, Game
+    },
+    vec_map::VecMap, vec_set::VecSet
+};
+
+/// How to pick the player on trial when several players are tied for the most nomination votes
+#[derive(Clone, Copy, Default, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum NominationTieBreak{
+    #[default]
+    NoTrial,
+    Random,
+    EarliestToThreshold,
+    Revote
+}
+
+#[derive(Default, Clone)]
+pub struct NominationTies{
+    /// Players with enough votes for a trial, in the order they reached the threshold
+    reached_threshold: Vec<PlayerReference>,
+    /// Only these players can be voted for while a revote is happening
+    revote_candidates: VecSet<PlayerReference>,
+}
+
+impl NominationTies{
+    pub fn on_phase_start(game: &mut Game, phase: PhaseType){
+        game.nomination_ties.reached_threshold.clear();
+        if phase != PhaseType::Nomination {
+            game.nomination_ties.revote_candidates.clear();
+        }
+    }
+
+    /// Called whenever nomination votes are counted
+    pub fn update_reached_threshold(game: &mut Game, voted_player_votes: &VecMap<PlayerReference, u8>){
+        let required_votes = game.nomination_votes_required();
+        let reached: VecSet<PlayerReference> = voted_player_votes.iter()
+            .filter(|(_, votes)| **votes >= required_votes)
+            .map(|(player, _)| *player)
+            .collect();
+
+        let reached_threshold = &mut game.nomination_ties.reached_threshold;
+        reached_threshold.retain(|player| reached.contains(player));
+        for player in reached.into_iter() {
+            if !reached_threshold.contains(&player) {
+                reached_threshold.push(player);
+            }
+        }
+    }
+
+    pub fn can_be_voted_for(game: &Game, player: PlayerReference)->bool{
+        game.nomination_ties.revote_candidates.is_empty() ||
+            game.nomination_ties.revote_candidates.contains(&player)
+    }
+
+    /// Returns the player who should be put on trial from the players with the most nomination votes.
+    /// Only called when a trial really starts, since a broken tie is announced
+    pub fn player_on_trial(game: &mut Game, nominated_players: &VecSet<PlayerReference>)->Option<PlayerReference>{
+        if nominated_players.len() < 2 {
+            return nominated_players.iter().next().copied();
+        }
+        Self::break_tie(game, nominated_players)
+    }
+
+    fn break_tie(game: &mut Game, tied_players: &VecSet<PlayerReference>)->Option<PlayerReference>{
+        let player_on_trial = match game.settings.nomination_tie_break {
+            NominationTieBreak::NoTrial
+            | NominationTieBreak::Revote => None,
+            NominationTieBreak::Random => tied_players.iter().choose(&mut rand::rng()).copied(),
+            NominationTieBreak::EarliestToThreshold => game.nomination_ties.reached_threshold.iter()
+                .find(|player| tied_players.contains(player))
+                .copied(),
+        }?;
+
+        game.add_message_to_chat_group(ChatGroup::All, ChatMessageVariant::NominationTieBroken {
+            tied_players: tied_players.iter().map(PlayerReference::index).collect(),
+            player_index: player_on_trial.index()
+        });
+
+        Some(player_on_trial)
+    }
+
+    /// Called at the end of nomination when nobody was put on trial.
+    /// Returns a new nomination phase between the tied players if a revote should happen.
+    /// A revote that ends in another tie does not start a second revote.
+    pub fn revote(game: &mut Game, trials_left: u8)->Option<PhaseState>{
+        if game.settings.nomination_tie_break != NominationTieBreak::Revote {return None}
+
+        let previous_candidates = std::mem::take(&mut game.nomination_ties.revote_candidates);
+        if !previous_candidates.is_empty() {return None}
+
+        let voted_player_votes = VoteWeight::nomination_votes(game);
+        let maximum_votes = *voted_player_votes.values().max()?;
+        if !game.nomination_votes_is_enough(maximum_votes) {return None}
+
+        let tied_players: VecSet<PlayerReference> = voted_player_votes.iter()
+            .filter(|(_, votes)| **votes == maximum_votes)
+            .map(|(player, _)| *player)
+            .collect();
+        if tied_players.len() < 2 {return None}
+
+        game.add_message_to_chat_group(ChatGroup::All, ChatMessageVariant::NominationRevote {
+            tied_players: tied_players.iter().map(PlayerReference::index).collect()
+        });
+        game.nomination_ties.revote_candidates = tied_players;
+
+        Some(PhaseState::Nomination {
+            trials_left,
+            nomination_time_remaining: PhaseStateMachine::get_phase_time_length(game, PhaseType::Nomination)
+        })
+    }
+}
@@ -2,7 +2,7 @@ use std::ops::Mul;
 
 use crate::{
     game::{
-        ability_input::*, attack_power::AttackPower, game_conclusion::GameConclusion, grave::GraveKiller, phase::PhaseType, player::PlayerReference, role::{Priority, Role}, role_list::RoleSet, Game
+        ability_input::*, attack_power::AttackPower, game_conclusion::GameConclusion, grave::GraveKiller, phase::PhaseType, player::PlayerReference, role::{Priority, Role}, role_list::RoleSet, settings::Settings, Game
     },
     vec_map::VecMap, vec_set::{vec_set, VecSet}
 };
@@ -36,9 +36,9 @@ impl Default for Pitchfork{
 }
 
 impl Pitchfork{
-    pub fn new(num_players: u8)->Self{
+    pub fn new(num_players: u8, settings: &Settings)->Self{
         Self{
-            pitchfork_uses_remaining: num_players.div_ceil(5),
+            pitchfork_uses_remaining: settings.role_uses(Role::Rabblerouser).unwrap_or(num_players.div_ceil(5)),
             ..Self::default()
         }
     }
@@ -91,6 +91,15 @@ impl Ord for Synopsis {
     }
 }
 
+impl Synopsis {
+    pub fn player_synopses(&self) -> &[PlayerSynopsis] {
+        &self.player_synopses
+    }
+    pub fn conclusion(&self) -> &GameConclusion {
+        &self.conclusion
+    }
+}
+
 #[derive(Clone, Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PlayerSynopsis {
@@ -98,6 +107,15 @@ pub struct PlayerSynopsis {
     won: bool
 }
 
+impl PlayerSynopsis {
+    pub fn crumbs(&self) -> &[SynopsisCrumb] {
+        &self.crumbs
+    }
+    pub fn won(&self) -> bool {
+        self.won
+    }
+}
+
 pub struct PartialPlayerSynopsis {
     crumbs: Vec<SynopsisCrumb>
 }
@@ -131,4 +149,17 @@ pub struct SynopsisCrumb {
     night: Option<u8>,
     role: Role,
     win_condition: WinCondition,
-}
\ No newline at end of file
+}
+
+impl SynopsisCrumb {
+    /// The night the change happened, or None if it happened during the day
+    pub fn night(&self) -> Option<u8> {
+        self.night
+    }
+    pub fn role(&self) -> Role {
+        self.role
+    }
+    pub fn win_condition(&self) -> &WinCondition {
+        &self.win_condition
+    }
+}
@@ -0,0 +1,64 @@
+use serde::Serialize;
+
+use crate::{game::{phase::PhaseType, player::PlayerReference, role::Role, Game}, strings::TidyableString};
+
+/// Every version of every player's will and who wrote it, sent to everyone when the game is over
+#[derive(Default, Clone)]
+pub struct WillHistory{
+    versions: Vec<WillVersion>
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct WillVersion{
+    pub player: PlayerReference,
+    pub day_number: u8,
+    pub phase: PhaseType,
+    pub author: WillAuthor,
+    pub will: String,
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[serde(tag = "type")]
+pub enum WillAuthor{
+    /// The player saved it themselves
+    Player,
+    /// A role changed what the player's grave shows, like a Forger's fake will.
+    /// The player's own will is unchanged, and the forged one is only seen if they die that night.
+    Role{player: PlayerReference, role: Role},
+}
+
+impl WillHistory{
+    pub const MAX_WILL_LENGTH: usize = 2000;
+    /// Older versions are dropped past this, so saving over and over can't fill up the server's memory
+    pub const MAX_VERSIONS_PER_PLAYER: usize = 50;
+
+    pub fn record(game: &mut Game, player: PlayerReference, author: WillAuthor, will: String){
+        let unchanged = game.will_history.versions.iter()
+            .rev()
+            .find(|version| version.player == player && version.author == author)
+            .is_some_and(|version| version.will == will);
+        if author == WillAuthor::Player && unchanged {return}
+
+        let version = WillVersion {
+            player,
+            day_number: game.day_number(),
+            phase: game.current_phase().phase(),
+            author,
+            will: will.truncate(Self::MAX_WILL_LENGTH)
+        };
+        if Self::versions_of(game, player).count() >= Self::MAX_VERSIONS_PER_PLAYER {
+            if let Some(oldest) = game.will_history.versions.iter().position(|version| version.player == player) {
+                game.will_history.versions.remove(oldest);
+            }
+        }
+        game.will_history.versions.push(version);
+    }
+    pub fn versions(game: &Game)->&Vec<WillVersion>{
+        &game.will_history.versions
+    }
+    pub fn versions_of(game: &Game, player: PlayerReference)->impl Iterator<Item = &WillVersion>{
+        Self::versions(game).iter().filter(move |version| version.player == player)
+    }
+}
@@ -1,5 +1,5 @@
 use crate::game::{
-    components::synopsis::SynopsisTracker, player::PlayerReference, win_condition::WinCondition, Game
+    components::{game_history::GameHistory, synopsis::SynopsisTracker}, player::PlayerReference, win_condition::WinCondition, Game
 };
 
 #[must_use = "Event must be invoked"]
@@ -13,6 +13,7 @@ impl OnConvert{
         Self{ player, old, new }
     }
     pub fn invoke(self, game: &mut Game){
+        GameHistory::on_convert(game, self.player, self.old.clone(), self.new.clone());
         SynopsisTracker::on_convert(game, self.player, self.old, self.new);
     }
 }
\ No newline at end of file
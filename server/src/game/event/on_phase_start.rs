@@ -1,7 +1,7 @@
 use crate::game::{
     ability_input::saved_controllers_map::SavedControllersMap, components::{
         cult::Cult, detained::Detained,
-        mafia::Mafia, night_visits::NightVisits,
+        mafia::Mafia, night_visits::NightVisits, nomination_ties::NominationTies,
         verdicts_today::VerdictsToday
     }, modifiers::Modifiers, phase::PhaseState, player::PlayerReference, Game
 };
@@ -22,6 +22,7 @@ impl OnPhaseStart{
         NightVisits::on_phase_start(game, self.phase.phase());
         Detained::on_phase_start(game, self.phase.phase());
         VerdictsToday::on_phase_start(game, self.phase.phase());
+        NominationTies::on_phase_start(game, self.phase.phase());
         Mafia::on_phase_start(game, self.phase.phase());
         Cult::on_phase_start(game, self.phase.phase());
         SavedControllersMap::on_phase_start(game, self.phase.phase());
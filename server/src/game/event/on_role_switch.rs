@@ -1,5 +1,5 @@
 use crate::game::{
-    components::{arsonist_doused::ArsonistDoused, cult::Cult, drunk_aura::DrunkAura, mafia::Mafia, synopsis::SynopsisTracker},
+    components::{arsonist_doused::ArsonistDoused, cult::Cult, drunk_aura::DrunkAura, game_history::GameHistory, mafia::Mafia, synopsis::SynopsisTracker},
     player::PlayerReference, 
     role::RoleState, 
     Game
@@ -26,5 +26,6 @@ impl OnRoleSwitch{
         DrunkAura::on_role_switch(game, self.player);
 
         SynopsisTracker::on_role_switch(game, self.player, self.old.role(), self.new.role());
+        GameHistory::on_role_switch(game, self.player, self.old.role(), self.new.role());
     }
 }
\ No newline at end of file
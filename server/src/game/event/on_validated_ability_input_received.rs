@@ -1,6 +1,7 @@
 use crate::game::{
     ability_input::AbilityInput,
     components::{forfeit_vote::ForfeitVote, syndicate_gun_item::SyndicateGunItem},
+    modifiers::Modifiers,
     player::PlayerReference,
     Game
 };
@@ -20,5 +21,6 @@ impl OnValidatedAbilityInputReceived{
         }
         SyndicateGunItem::on_validated_ability_input_received(game, self.actor_ref, self.input.clone());
         ForfeitVote::on_validated_ability_input_received(game, self.actor_ref, self.input.clone());
+        Modifiers::on_validated_ability_input_received(game, self.actor_ref, self.input.clone());
     }
 }
\ No newline at end of file
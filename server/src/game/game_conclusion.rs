@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 
-use super::{player::PlayerReference, role::Role, role_list::RoleSet, win_condition::WinCondition, Game};
+use crate::vec_set::VecSet;
+
+use super::{components::insider_group::InsiderGroupID, player::PlayerReference, role::Role, role_list::RoleSet, settings::Settings, win_condition::WinCondition, Game};
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
 #[serde(rename_all = "camelCase")]
@@ -16,8 +18,38 @@ pub enum GameConclusion {
     NiceList,
     NaughtyList,
 
+    /// Index into the host's custom factions
+    Custom(u8),
+
     Draw
 }
+
+/// A faction made by the host, for three faction or team versus team games.
+/// Outlines join it by having it in their win condition.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomFaction {
+    pub name: String,
+    /// Members with these roles keep the game running, on top of the roles that always do
+    pub keeps_game_running: Vec<Role>,
+}
+impl CustomFaction {
+    pub const MAX_NAME_LENGTH: usize = 30;
+    pub const MAX_COUNT: usize = 8;
+}
+
+/// Why the host's custom factions weren't accepted
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum CustomFactionError {
+    TooManyFactions,
+    InvalidName{faction: u8},
+    DuplicateName{faction: u8},
+    DuplicateRole{faction: u8, role: Role},
+    /// Role list options use this faction, so it can't be removed or moved
+    FactionInUse{faction: u8},
+}
+
 impl GameConclusion {
     pub fn all()->Vec<GameConclusion>{
         vec![
@@ -37,39 +69,49 @@ impl GameConclusion {
     }
     ///either return Some(EndGameCondition) or None (if the game is not over yet)
     pub fn game_is_over(game: &Game)->Option<GameConclusion> {
+        Self::game_is_over_for_living_players(
+            &PlayerReference::all_players(game)
+                .filter(|player| player.alive(game))
+                .map(|player| (player.role(game), player.win_condition(game), player.keeps_game_running(game)))
+                .collect::<Vec<_>>()
+        )
+    }
+    /// Same as game_is_over, but only needs each living player's role, win condition, and whether they keep the game running
+    pub fn game_is_over_for_living_players(living_players: &[(Role, &WinCondition, bool)])->Option<GameConclusion> {
 
         //Special wildcard case
-        let living_roles = PlayerReference::all_players(game).filter_map(|player|{
-            if player.alive(game){
-                Some(player.role(game))
-            }else{
-                None
-            }
-        }).collect::<Vec<_>>();
-
-        if living_roles.iter().all(|role|matches!(role, Role::Wildcard|Role::TrueWildcard)) && living_roles.len() > 1 {
+        if living_players.iter().all(|(role, _, _)|matches!(role, Role::Wildcard|Role::TrueWildcard)) && living_players.len() > 1 {
             return None;
         }
         
         //if nobody is left to hold game hostage
-        if !PlayerReference::all_players(game).any(|player| player.alive(game) && player.keeps_game_running(game)){
+        if !living_players.iter().any(|(_, _, keeps_game_running)| *keeps_game_running){
             return Some(GameConclusion::Draw);
         }
 
+        //custom factions only matter if someone is in them, and a draw is checked last
+        let custom_conclusions: VecSet<GameConclusion> = living_players.iter()
+            .filter_map(|(_, win_condition, _)| win_condition.required_resolution_states_for_win())
+            .flatten()
+            .filter(|resolution| matches!(resolution, GameConclusion::Custom(_)))
+            .collect();
+
         //find one end game condition that everyone agrees on
-        GameConclusion::all().into_iter().find(|resolution| 
-            PlayerReference::all_players(game)
-                .filter(|p|p.alive(game))
-                .filter(|p|p.keeps_game_running(game))
-                .all(|p|
-                    match p.win_condition(game){
+        GameConclusion::all().into_iter()
+            .filter(|resolution| *resolution != GameConclusion::Draw)
+            .chain(custom_conclusions)
+            .chain(std::iter::once(GameConclusion::Draw))
+            .find(|resolution| 
+            living_players.iter()
+                .filter(|(_, _, keeps_game_running)| *keeps_game_running)
+                .all(|(_, win_condition, _)|
+                    match win_condition{
                         WinCondition::GameConclusionReached{win_if_any} => win_if_any.contains(resolution),
                         WinCondition::RoleStateWon => true,
                     }
                 )
         )
-    }
-    
+    }    
 
     ///Town, Mafia, Cult, NK
     /// Has the ability to consistently kill till the end of the game
@@ -89,6 +131,26 @@ impl GameConclusion {
             }
         }
     }
+    /// Used for living players, and by role list analysis before anyone has a role
+    pub fn player_keeps_game_running(
+        settings: &Settings, role: Role, win_condition: &WinCondition, in_revealed_group: impl Fn(InsiderGroupID)->bool
+    )->bool{
+        in_revealed_group(InsiderGroupID::Mafia) ||
+        in_revealed_group(InsiderGroupID::Cult) ||
+        win_condition.is_loyalist_for(GameConclusion::Town) ||
+        Self::keeps_game_running_for_custom_faction(settings, role, win_condition) ||
+        Self::keeps_game_running(role)
+    }
+    /// Whether the host made this role keep the game running for one of the custom factions in this win condition
+    pub fn keeps_game_running_for_custom_faction(settings: &Settings, role: Role, win_condition: &WinCondition)->bool{
+        let Some(win_if_any) = win_condition.required_resolution_states_for_win() else {return false};
+
+        win_if_any.iter().any(|resolution| match resolution {
+            GameConclusion::Custom(id) => settings.custom_factions.get(*id as usize)
+                .is_some_and(|faction| faction.keeps_game_running.contains(&role)),
+            _ => false
+        })
+    }
 }
 
 
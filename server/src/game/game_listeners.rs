@@ -1,12 +1,12 @@
 use crate::packet::ToClientPacket;
 
 use super::{
-    chat::{ChatGroup, ChatMessageVariant}, components::synopsis::SynopsisTracker, game_conclusion::GameConclusion, grave::GraveReference, phase::{PhaseState, PhaseStateMachine, PhaseType}, player::PlayerReference, role::Role, Game, GameOverReason
+    chat::{ChatGroup, ChatMessageVariant}, components::{night_resolution_trace::NightResolutionTrace, synopsis::SynopsisTracker, game_history::{GameHistory, GameHistoryEventKind}}, game_conclusion::GameConclusion, grave::GraveReference, phase::{PhaseState, PhaseStateMachine, PhaseType}, player::PlayerReference, role::Role, Game, GameOverReason
 };
 
 //Event listerner functions for game defined here
 impl Game{
-    pub fn on_phase_start(&mut self, _phase: PhaseType){
+    pub fn on_phase_start(&mut self, phase: PhaseType){
         self.send_packet_to_all(ToClientPacket::Phase { 
             phase: self.current_phase().clone(),
             day_number: self.phase_machine.day_number,
@@ -17,6 +17,9 @@ impl Game{
                 player.get_current_send_chat_groups(self).into_iter().collect()
             });
         }
+        if phase == PhaseType::Obituary && self.settings.spectator_settings.omniscient_view {
+            self.send_packet_to_spectators(ToClientPacket::NightResolutionTrace{ nights: NightResolutionTrace::nights(self).clone() });
+        }
     }
     pub fn on_any_death(&mut self, _dead_player: PlayerReference){
         for player in PlayerReference::all_players(self){
@@ -26,11 +29,12 @@ impl Game{
         }
     }
     pub fn on_game_ending(&mut self, conclusion: GameConclusion){
-        let synopsis = SynopsisTracker::get(self, conclusion);
+        let synopsis = SynopsisTracker::get(self, conclusion.clone());
 
         PhaseStateMachine::next_phase(self, Some(PhaseState::Recess));
         self.add_message_to_chat_group(ChatGroup::All, ChatMessageVariant::GameOver { synopsis });
-        self.send_packet_to_all(ToClientPacket::GameOver{ reason: GameOverReason::Draw });
+        GameHistory::record(self, GameHistoryEventKind::GameOver { conclusion });
+        self.send_game_over_data(GameOverReason::Draw);
         
         self.ticking = false;
     }
@@ -54,6 +58,10 @@ impl Game{
 
         if old == new {return;}
 
+        if self.settings.spectator_settings.omniscient_view {
+            self.send_packet_to_spectators(ToClientPacket::new_player_roles(self));
+        }
+
         for player_ref in PlayerReference::all_players(self){
             player_ref.remove_role_label(self, actor);
         }
@@ -50,6 +50,8 @@ pub enum GraveDeathCause {
 #[serde(tag = "type", content = "value")]
 pub enum GraveKiller {
     RoleSet(RoleSet),
+    /// The name of one of the host's custom role sets
+    CustomRoleSet(String),
     Role(Role),
     Suicide,
     Quit,
@@ -6,6 +6,8 @@ pub mod role;
 pub mod visit;
 pub mod verdict;
 pub mod role_list;
+pub mod role_list_generation;
+pub mod role_list_analysis;
 pub mod settings;
 pub mod game_conclusion;
 pub mod components;
@@ -20,6 +22,9 @@ pub mod modifiers;
 pub mod win_condition;
 pub mod role_outline_reference;
 pub mod ability_input;
+pub mod vote_weight;
+pub mod whisper;
+pub mod will_template;
 
 use std::time::Duration;
 use ability_input::saved_controllers_map::SavedControllersMap;
@@ -28,10 +33,17 @@ use components::drunk_aura::DrunkAura;
 use components::love_linked::LoveLinked;
 use components::mafia::Mafia;
 use components::night_visits::NightVisits;
+use components::nomination_ties::NominationTies;
+use whisper::Whispers;
+use components::night_resolution_trace::NightResolutionTrace;
+use components::will_history::WillHistory;
+use components::game_history::{GameHistory, GameHistoryEventKind};
 use components::pitchfork::Pitchfork;
 use components::mafia_recruits::MafiaRecruits;
 use components::poison::Poison;
 use components::detained::Detained;
+use components::enfranchised::Enfranchised;
+use components::muted::Muted;
 use components::insider_group::InsiderGroupID;
 use components::insider_group::InsiderGroups;
 use components::syndicate_gun_item::SyndicateGunItem;
@@ -40,11 +52,13 @@ use components::verdicts_today::VerdictsToday;
 use event::on_tick::OnTick;
 use modifiers::ModifierType;
 use modifiers::Modifiers;
+use modifiers::two_thirds_majority::TwoThirdsMajority;
 use event::before_initial_role_creation::BeforeInitialRoleCreation;
 use rand::seq::SliceRandom;
 use role_list::RoleAssignment;
 use role_list::RoleOutlineOptionInsiderGroups;
 use role_list::RoleOutlineOptionWinCondition;
+use role_list_generation::RoleListGenerationError;
 use role_outline_reference::RoleOutlineReference;
 use serde::Serialize;
 use win_condition::WinCondition;
@@ -55,7 +69,7 @@ use crate::game::player::PlayerIndex;
 use crate::packet::ToClientPacket;
 use crate::vec_map::VecMap;
 use crate::vec_set::VecSet;
-use chat::{ChatMessageVariant, ChatGroup, ChatMessage};
+use chat::{ChatMessageVariant, ChatGroup, ChatMessage, ChatMessageID};
 use player::PlayerReference;
 use player::Player;
 use phase::PhaseStateMachine;
@@ -74,20 +88,22 @@ use self::phase::PhaseState;
 use self::player::PlayerInitializeParameters;
 use self::spectator::{
     spectator_pointer::{
-        SpectatorIndex, SpectatorPointer
+        SpectatorID, SpectatorPointer
     },
     Spectator,
     SpectatorInitializeParameters
 };
-use self::role::RoleState;
 use self::verdict::Verdict;
+use self::vote_weight::{VoteType, VoteWeight};
 
 
 pub struct Game {
     pub settings : Settings,
 
-    pub spectators: Vec<Spectator>,
-    pub spectator_chat_messages: Vec<ChatMessageVariant>,
+    pub spectators: VecMap<SpectatorID, Spectator>,
+    next_spectator_id: SpectatorID,
+    pub spectator_chat_messages: Vec<ChatMessage>,
+    next_chat_message_id: ChatMessageID,
 
     /// indexed by role outline reference
     pub assignments: Vec<(PlayerReference, RoleOutlineReference, RoleAssignment)>,
@@ -120,6 +136,13 @@ pub struct Game {
     pub detained: Detained,
     pub confused: Confused,
     pub drunk_aura: DrunkAura,
+    pub enfranchised: Enfranchised,
+    pub muted: Muted,
+    pub nomination_ties: NominationTies,
+    pub whispers: Whispers,
+    pub night_resolution_trace: NightResolutionTrace,
+    pub will_history: WillHistory,
+    pub game_history: GameHistory,
     pub synopsis_tracker: SynopsisTracker
 }
 
@@ -128,7 +151,8 @@ pub struct Game {
 pub enum RejectStartReason {
     GameEndsInstantly,
     RoleListTooSmall,
-    RoleListCannotCreateRoles,
+    #[serde(rename_all = "camelCase")]
+    RoleListGenerationFailed{error: RoleListGenerationError},
     ZeroTimeGame,
     PlayerDisconnected
 }
@@ -156,15 +180,15 @@ impl Game {
         let (mut game, assignments) = loop {
 
             if role_generation_tries >= MAX_ROLE_GENERATION_TRIES {
-                return Err(RejectStartReason::RoleListCannotCreateRoles);
+                return Err(RejectStartReason::GameEndsInstantly);
             }
 
             let settings = settings.clone();
             let role_list = settings.role_list.clone();
 
-            let random_outline_assignments = match role_list.create_random_role_assignments(&settings.enabled_roles){
-                Some(roles) => {roles},
-                None => {return Err(RejectStartReason::RoleListCannotCreateRoles);}
+            let random_outline_assignments = match role_list.create_random_role_assignments(&settings){
+                Ok(roles) => {roles},
+                Err(error) => {return Err(RejectStartReason::RoleListGenerationFailed { error });}
             };
 
             let assignments = Self::assign_players_to_assignments(random_outline_assignments);            
@@ -204,16 +228,23 @@ impl Game {
             let num_players = new_players.len() as u8;
 
             let mut game = Self{
-                pitchfork: Pitchfork::new(num_players),
+                pitchfork: Pitchfork::new(num_players, &settings),
 
                 assignments: assignments.clone(),
                 ticking: true,
-                spectators: spectators.clone().into_iter().map(Spectator::new).collect(),
+                spectators: spectators.clone().into_iter()
+                    .map(|params| Spectator::new(params, settings.spectator_settings.stream_delay()))
+                    .enumerate()
+                    .map(|(id, spectator)| (id as SpectatorID, spectator))
+                    .collect(),
+                next_spectator_id: spectators.len() as SpectatorID,
                 spectator_chat_messages: Vec::new(),
+                next_chat_message_id: 0,
                 players: new_players.into_boxed_slice(),
                 graves: Vec::new(),
                 phase_machine: PhaseStateMachine::new(settings.phase_times.clone()),
-                modifiers: Modifiers::default_from_settings(settings.enabled_modifiers.clone()),
+                modifiers: Modifiers::from_settings(settings.enabled_modifiers.clone()),
+                revealed_groups: InsiderGroups::new(&settings),
                 settings,
 
                 saved_controllers: SavedControllersMap::default(),
@@ -228,10 +259,16 @@ impl Game {
                 verdicts_today: VerdictsToday::default(),
                 poison: Poison::default(),
 
-                revealed_groups: InsiderGroups::default(),
                 detained: Detained::default(),
                 confused: Confused::default(),
                 drunk_aura: DrunkAura::default(),
+                enfranchised: Enfranchised::default(),
+                muted: Muted::default(),
+                nomination_ties: NominationTies::default(),
+                whispers: Whispers::default(),
+                night_resolution_trace: NightResolutionTrace::default(),
+                will_history: WillHistory::default(),
+                game_history: GameHistory::default(),
                 synopsis_tracker: SynopsisTracker::new(num_players)
             };
 
@@ -263,7 +300,7 @@ impl Game {
         };
 
         if game.game_is_over() {
-            return Err(RejectStartReason::RoleListCannotCreateRoles);
+            return Err(RejectStartReason::GameEndsInstantly);
         }
         
         game.send_packet_to_all(ToClientPacket::StartGame);
@@ -310,7 +347,7 @@ impl Game {
         }
 
         //reveal groups
-        for group in InsiderGroupID::all() {
+        for group in InsiderGroupID::all(&game) {
             group.reveal_group_players(&mut game);
         }
 
@@ -343,28 +380,18 @@ impl Game {
 
     /// Returns a tuple containing the number of guilty votes and the number of innocent votes
     pub fn count_verdict_votes(&self, player_on_trial: PlayerReference)->(u8,u8){
-        let mut guilty = 0;
-        let mut innocent = 0;
+        let mut guilty: u8 = 0;
+        let mut innocent: u8 = 0;
         for player_ref in PlayerReference::all_players(self){
             if !player_ref.alive(self) || player_ref == player_on_trial {
                 continue;
             }
-            let mut voting_power = 1;
-            if let RoleState::Mayor(mayor) = player_ref.role_state(self).clone(){
-                if mayor.revealed {
-                    voting_power += 2;
-                }
-            }
-            if let RoleState::Politician(politician) = player_ref.role_state(self).clone(){
-                if politician.revealed {
-                    voting_power += 2;
-                }
-            }
+            let voting_power = VoteWeight::get(self, player_ref, VoteType::Verdict);
             
             match player_ref.verdict(self) {
-                Verdict::Innocent => innocent += voting_power,
+                Verdict::Innocent => innocent = innocent.saturating_add(voting_power),
                 Verdict::Abstain => {},
-                Verdict::Guilty => guilty += voting_power,
+                Verdict::Guilty => guilty = guilty.saturating_add(voting_power),
             }
         }
         (guilty, innocent)
@@ -372,31 +399,7 @@ impl Game {
     
     /// this is sent to the players whenever this function is called
     fn create_voted_player_map(&self) -> VecMap<PlayerReference, u8> {
-        let mut voted_player_votes: VecMap<PlayerReference, u8> = VecMap::new();
-
-        for player in PlayerReference::all_players(self){
-            if !player.alive(self) { continue }
-
-            let Some(voted_player) = player.chosen_vote(self) else { continue };
-
-            let mut voting_power = 1;
-            if let RoleState::Mayor(mayor) = player.role_state(self).clone() {
-                if mayor.revealed {
-                    voting_power = 3;
-                }
-            }
-            else if let RoleState::Politician(politician) = player.role_state(self).clone() {
-                if politician.revealed {
-                    voting_power = 3;
-                }
-            }
-
-            if let Some(num_votes) = voted_player_votes.get_mut(&voted_player) {
-                *num_votes += voting_power;
-            } else {
-                voted_player_votes.insert(voted_player, voting_power);
-            }
-        }
+        let voted_player_votes = VoteWeight::nomination_votes(self);
 
         self.send_packet_to_all(
             ToClientPacket::PlayerVotes { votes_for_player: 
@@ -406,44 +409,45 @@ impl Game {
 
         voted_player_votes
     }
-    /// Returns the player who is meant to be put on trial
-    /// None if its not nomination
+    /// Returns the players with the most nomination votes, more than one if they are tied.
+    /// Empty if its not nomination or nobody has enough votes.
+    /// Ties aren't broken here, because votes are counted again every time they might change
+    pub fn count_nomination(&mut self)->VecSet<PlayerReference>{
+        if !matches!(self.current_phase(), PhaseState::Nomination { .. }) {return VecSet::new()}
+        if Modifiers::modifier_is_enabled(self, ModifierType::PluralityElimination) {return VecSet::new()}
+
+        let voted_player_votes = self.create_voted_player_map();
+        NominationTies::update_reached_threshold(self, &voted_player_votes);
+
+        let Some(maximum_votes) = voted_player_votes.values().max().copied() else {return VecSet::new()};
+        if !self.nomination_votes_is_enough(maximum_votes) {return VecSet::new()}
+
+        voted_player_votes.iter()
+            .filter(|(_, votes)| **votes == maximum_votes)
+            .map(|(player, _)| *player)
+            .collect()
+    }
+    /// Returns the player who was put on trial
+    /// None if the trial isn't started instantly, so it waits for the end of nomination
     /// None if nobody has enough votes
-    /// None if there is a tie
+    /// None if there is a tie that the tie break setting doesn't resolve
     pub fn count_nomination_and_start_trial(&mut self, start_trial_instantly: bool)->Option<PlayerReference>{
 
         let &PhaseState::Nomination { trials_left, .. } = self.current_phase() else {return None};
 
-        let voted_player_votes = self.create_voted_player_map();
-
-        let mut voted_player = None;
+        let nominated_players = self.count_nomination();
+        if !start_trial_instantly {return None}
 
-        if let Some(maximum_votes) = voted_player_votes.values().max() {
-            if self.nomination_votes_is_enough(*maximum_votes){
-                let max_votes_players: VecSet<PlayerReference> = voted_player_votes.iter()
-                    .filter(|(_, votes)| **votes == *maximum_votes)
-                    .map(|(player, _)| *player)
-                    .collect();
+        let player_on_trial = NominationTies::player_on_trial(self, &nominated_players)?;
+        self.send_packet_to_all(ToClientPacket::PlayerOnTrial { player_index: player_on_trial.index() } );
 
-                if max_votes_players.len() == 1 {
-                    voted_player = max_votes_players.iter().next().cloned();
-                }
-            }
-        }
-        
-        if start_trial_instantly {
-            if let Some(player_on_trial) = voted_player {
-                self.send_packet_to_all(ToClientPacket::PlayerOnTrial { player_index: player_on_trial.index() } );
-                
-                PhaseStateMachine::next_phase(self, Some(PhaseState::Testimony {
-                    trials_left: trials_left.saturating_sub(1), 
-                    player_on_trial, 
-                    nomination_time_remaining: self.phase_machine.get_time_remaining()
-                }));
-            }
-        }
+        PhaseStateMachine::next_phase(self, Some(PhaseState::Testimony {
+            trials_left: trials_left.saturating_sub(1), 
+            player_on_trial, 
+            nomination_time_remaining: self.phase_machine.get_time_remaining()
+        }));
 
-        voted_player
+        Some(player_on_trial)
     }
 
     
@@ -455,8 +459,8 @@ impl Game {
             .filter(|p| p.alive(self) && !p.forfeit_vote(self))
             .count() as u8;
 
-        if Modifiers::modifier_is_enabled(self, ModifierType::TwoThirdsMajority) {
-            (eligible_voters + 1) * 2 / 3
+        if let Some(majority) = TwoThirdsMajority::get(self) {
+            majority.nomination_votes_required(eligible_voters)
         } else {
             1 + eligible_voters / 2
         }
@@ -481,7 +485,11 @@ impl Game {
 
     pub fn tick(&mut self, time_passed: Duration){
 
-        if !self.ticking { return }
+        if !self.ticking {
+            // Spectators with a stream delay still need to see how the game ended
+            self.spectators.values_mut().for_each(|s|s.tick(time_passed));
+            return
+        }
 
         if let Some(conclusion) = GameConclusion::game_is_over(self) {
             OnGameEnding::new(conclusion).invoke(self);
@@ -491,7 +499,8 @@ impl Game {
             self.add_message_to_chat_group(ChatGroup::All, ChatMessageVariant::GameOver { 
                 synopsis: SynopsisTracker::get(self, GameConclusion::Draw)
             });
-            self.send_packet_to_all(ToClientPacket::GameOver{ reason: GameOverReason::ReachedMaxDay });
+            GameHistory::record(self, GameHistoryEventKind::GameOver { conclusion: GameConclusion::Draw });
+            self.send_game_over_data(GameOverReason::ReachedMaxDay);
             self.ticking = false;
             return;
         }
@@ -501,6 +510,7 @@ impl Game {
         }
         PlayerReference::all_players(self).for_each(|p|p.tick(self, time_passed));
         SpectatorPointer::all_spectators(self).for_each(|s|s.tick(self, time_passed));
+        Whispers::tick(self, time_passed);
 
         self.phase_machine.time_remaining = self.phase_machine.time_remaining.saturating_sub(time_passed);
 
@@ -508,6 +518,10 @@ impl Game {
     }
 
     pub fn add_grave(&mut self, grave: Grave){
+        // Grave added listeners can obscure the grave, so omniscient spectators get it first
+        if self.settings.spectator_settings.omniscient_view {
+            self.send_packet_to_spectators(ToClientPacket::UnobscuredGrave { grave: grave.clone() });
+        }
         self.graves.push(grave.clone());
         if let Some(grave_ref) = GraveReference::new(
             self, 
@@ -517,19 +531,28 @@ impl Game {
                 .expect("There can not be more than u8::MAX graves"))
         {
             OnGraveAdded::new(grave_ref).invoke(self);
+
+            GameHistory::record(self, GameHistoryEventKind::Grave {
+                shown: grave_ref.deref(self).clone(),
+                role: grave.player.role(self),
+                original: grave,
+            });
         }
     }
 
     pub fn add_message_to_chat_group(&mut self, group: ChatGroup, variant: ChatMessageVariant){
-        let message = ChatMessage::new_non_private(variant.clone(), group.clone());
+        let message = ChatMessage::new_non_private(self.next_chat_message_id(), variant, group.clone());
 
         for player_ref in group.all_players_in_group(self){
-            player_ref.add_chat_message(self, message.clone());
+            player_ref.add_chat_message(self, message.for_player(player_ref.index()));
             player_ref.send_chat_messages(self);
         }
 
-        if group == ChatGroup::All {
-            self.add_chat_message_to_spectators(variant);
+        if
+            group == ChatGroup::All || group == ChatGroup::Spectator ||
+            self.settings.spectator_settings.omniscient_view
+        {
+            self.add_chat_message_to_spectators(message);
         }
     }
     pub fn add_messages_to_chat_group(&mut self, group: ChatGroup, messages: Vec<ChatMessageVariant>){
@@ -537,33 +560,81 @@ impl Game {
             self.add_message_to_chat_group(group.clone(), message);
         }
     }
-    pub fn add_chat_message_to_spectators(&mut self, message: ChatMessageVariant){
-        for spectator in self.spectators.iter_mut(){
+    /// Every player gets the same message, so deleting it removes it from all of them
+    pub fn add_private_message_to_players(&mut self, players: impl IntoIterator<Item = PlayerReference>, variant: ChatMessageVariant){
+        let message = ChatMessage::new_private(self.next_chat_message_id(), variant);
+
+        for player_ref in players {
+            player_ref.add_chat_message(self, message.clone());
+        }
+    }
+    pub fn next_chat_message_id(&mut self)->ChatMessageID{
+        let id = self.next_chat_message_id;
+        self.next_chat_message_id += 1;
+        id
+    }
+    /// Removes the message from everyone who received it, and tells their clients to remove it too
+    pub fn delete_chat_message(&mut self, id: ChatMessageID){
+        for player_ref in PlayerReference::all_players(self){
+            if player_ref.remove_chat_message(self, id) {
+                player_ref.send_packet(self, ToClientPacket::ChatMessageDeleted { id });
+            }
+        }
+
+        let spectators_had_message = self.spectator_chat_messages.iter().any(|message| message.id == id);
+        self.spectator_chat_messages.retain(|message| message.id != id);
+        for spectator in self.spectators.values_mut(){
+            spectator.queued_chat_messages.retain(|message| message.id != id);
+        }
+        if spectators_had_message {
+            self.send_packet_to_spectators(ToClientPacket::ChatMessageDeleted { id });
+        }
+    }
+    pub fn add_chat_message_to_spectators(&mut self, message: ChatMessage){
+        for spectator in self.spectators.values_mut(){
             spectator.queued_chat_messages.push(message.clone());
         }
         self.spectator_chat_messages.push(message);
     }
 
-    pub fn add_spectator(&mut self, params: SpectatorInitializeParameters) -> SpectatorIndex {
-        self.spectators.push(Spectator::new(params));
-        let spectator_pointer = SpectatorPointer::new(self.spectators.len() as SpectatorIndex - 1);
+    pub fn add_spectator(&mut self, params: SpectatorInitializeParameters) -> SpectatorID {
+        let spectator_pointer = SpectatorPointer::new(self.next_spectator_id);
+        self.next_spectator_id += 1;
+        self.spectators.insert(spectator_pointer.id, Spectator::new(params, self.settings.spectator_settings.stream_delay()));
 
         spectator_pointer.send_join_game_data(self);
 
-        spectator_pointer.index
+        spectator_pointer.id
     }
-    pub fn remove_spectator(&mut self, i: SpectatorIndex){
-        self.spectators.remove(i as usize);
+    pub fn remove_spectator(&mut self, id: SpectatorID){
+        self.spectators.remove(&id);
     }
 
     pub fn send_packet_to_all(&self, packet: ToClientPacket){
         for player_ref in PlayerReference::all_players(self){
             player_ref.send_packet(self, packet.clone());
         }
-        for spectator in self.spectators.iter(){
+        self.send_packet_to_spectators(packet);
+    }
+    pub fn send_packet_to_spectators(&self, packet: ToClientPacket){
+        for spectator in self.spectators.values(){
             spectator.send_packet(packet.clone());
         }
     }
+    /// Everything that's only revealed once the game is over
+    pub fn game_over_packets(&self, reason: GameOverReason)->Vec<ToClientPacket>{
+        vec![
+            ToClientPacket::GameOver{ reason },
+            ToClientPacket::NightResolutionTrace{ nights: NightResolutionTrace::nights(self).clone() },
+            ToClientPacket::WillHistory{ versions: WillHistory::versions(self).clone() },
+            ToClientPacket::GameHistory{ history: GameHistory::export(self) },
+        ]
+    }
+    pub fn send_game_over_data(&self, reason: GameOverReason){
+        for packet in self.game_over_packets(reason){
+            self.send_packet_to_all(packet);
+        }
+    }
 }
 
 pub mod test {
@@ -571,7 +642,7 @@ pub mod test {
     use super::{
         ability_input::saved_controllers_map::SavedControllersMap,
         components::{
-            arsonist_doused::ArsonistDoused, cult::Cult, insider_group::InsiderGroupID,
+            arsonist_doused::ArsonistDoused, cult::Cult, insider_group::{InsiderGroupID, InsiderGroups},
             love_linked::LoveLinked, mafia::Mafia,
             mafia_recruits::MafiaRecruits, night_visits::NightVisits,
             pitchfork::Pitchfork, poison::Poison,
@@ -580,7 +651,7 @@ pub mod test {
         }, 
         event::{before_initial_role_creation::BeforeInitialRoleCreation, on_game_start::OnGameStart},
         phase::PhaseStateMachine, player::{test::mock_player, PlayerReference},
-        role::Role, settings::Settings, Game, RejectStartReason
+        role::Role, role_list::{RoleOutlineOptionInsiderGroups, RoleOutlineOptionWinCondition}, settings::Settings, win_condition::WinCondition, Game, RejectStartReason
     };
     
     pub fn mock_game(settings: Settings, number_of_players: usize) -> Result<Game, RejectStartReason> {
@@ -593,9 +664,9 @@ pub mod test {
         let settings = settings.clone();
         let role_list = settings.role_list.clone();
         
-        let random_outline_assignments = match role_list.create_random_role_assignments(&settings.enabled_roles){
-            Some(roles) => {roles},
-            None => {return Err(RejectStartReason::RoleListCannotCreateRoles);}
+        let random_outline_assignments = match role_list.create_random_role_assignments(&settings){
+            Ok(roles) => {roles},
+            Err(error) => {return Err(RejectStartReason::RoleListGenerationFailed { error });}
         };
 
         let assignments = Game::assign_players_to_assignments(random_outline_assignments);
@@ -616,15 +687,18 @@ pub mod test {
         }
 
         let mut game = Game{
-            pitchfork: Pitchfork::new(number_of_players as u8),
+            pitchfork: Pitchfork::new(number_of_players as u8, &settings),
             
             assignments,
             ticking: true,
-            spectators: Vec::new(),
+            spectators: Default::default(),
+            next_spectator_id: 0,
             spectator_chat_messages: Vec::new(),
+            next_chat_message_id: 0,
             players: players.into_boxed_slice(),
             graves: Vec::new(),
             phase_machine: PhaseStateMachine::new(settings.phase_times.clone()),
+            revealed_groups: InsiderGroups::new(&settings),
             settings,
 
             saved_controllers: SavedControllersMap::default(),
@@ -639,10 +713,16 @@ pub mod test {
             verdicts_today: VerdictsToday::default(),
             poison: Poison::default(),
             modifiers: Default::default(),
-            revealed_groups: Default::default(),
             detained: Default::default(),
             confused: Default::default(),
             drunk_aura: Default::default(),
+            enfranchised: Default::default(),
+            muted: Default::default(),
+            nomination_ties: Default::default(),
+            whispers: Default::default(),
+            night_resolution_trace: Default::default(),
+            will_history: Default::default(),
+            game_history: Default::default(),
             synopsis_tracker: SynopsisTracker::new(number_of_players as u8)
         };
 
@@ -650,10 +730,24 @@ pub mod test {
         for player in PlayerReference::all_players(&game){
             let role_data = player.role(&game).new_state(&game);
 
-            player.set_win_condition(&mut game, role_data.clone().default_win_condition());
+            // Players were given roles in assignment order above, not by the assignment's player
+            let assignment = game.assignments.get(player.index() as usize).map(|(_,_,a)|a.clone());
+
+            let win_condition = match assignment.as_ref().map(|a|&a.win_condition) {
+                Some(RoleOutlineOptionWinCondition::GameConclusionReached { win_if_any }) => WinCondition::GameConclusionReached {
+                    win_if_any: win_if_any.iter().cloned().collect()
+                },
+                _ => role_data.clone().default_win_condition(),
+            };
+            player.set_win_condition(&mut game, win_condition);
+
+            let insider_groups = match assignment.as_ref().map(|a|&a.insider_groups) {
+                Some(RoleOutlineOptionInsiderGroups::Custom { insider_groups }) => insider_groups.iter().cloned().collect(),
+                _ => role_data.clone().default_revealed_groups(),
+            };
         
             InsiderGroupID::start_game_set_player_revealed_groups(
-                role_data.clone().default_revealed_groups(),
+                insider_groups,
                 &mut game,
                 player
             );
@@ -1,6 +1,8 @@
+use serde::{Deserialize, Serialize};
+
 use super::{ModifierTrait, ModifierType};
 
-#[derive(Clone, Default, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct DeadCanChat;
 
 /*
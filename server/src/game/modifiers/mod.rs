@@ -11,6 +11,8 @@ pub mod no_whispers;
 pub mod no_night_chat;
 pub mod no_chat;
 pub mod scheduled_nominations;
+pub mod plurality_elimination;
+pub mod secret_ballot;
 
 use dead_can_chat::DeadCanChat;
 use no_abstaining::NoAbstaining;
@@ -20,22 +22,26 @@ use no_night_chat::NoNightChat;
 use no_trial::NoTrialPhases;
 use no_whispers::NoWhispers;
 use obscured_graves::ObscuredGraves;
+use plurality_elimination::PluralityElimination;
 use random_love_links::RandomLoveLinks;
 use no_death_cause::NoDeathCause;
 use role_set_grave_killers::RoleSetGraveKillers;
 use scheduled_nominations::ScheduledNominations;
+use secret_ballot::SecretBallot;
 
 use serde::{Deserialize, Serialize};
 use two_thirds_majority::TwoThirdsMajority;
 
-use crate::{vec_map::VecMap, vec_set::VecSet};
+use crate::vec_map::VecMap;
 
-use super::{grave::GraveReference, Game};
+use super::{grave::GraveReference, vote_weight::VoteType, Game};
 
 
 #[enum_delegate::register]
 pub trait ModifierTrait where Self: Clone + Sized{
     fn on_ability_input_received(self, _game: &mut Game, _actor_ref: crate::game::player::PlayerReference, _input: crate::game::ability_input::AbilityInput) {}
+    fn on_validated_ability_input_received(self, _game: &mut Game, _actor_ref: crate::game::player::PlayerReference, _input: crate::game::ability_input::AbilityInput) {}
+    fn controller_parameters_map(self, _game: &Game) -> crate::game::ability_input::ControllerParametersMap {crate::game::ability_input::ControllerParametersMap::default()}
     fn on_night_priority(self, _game: &mut Game, _priority: crate::game::role::Priority) {}
     fn before_phase_end(self, _game: &mut Game, _phase: super::phase::PhaseType) {}
     fn on_phase_start(self, _game: &mut Game, _phase: super::phase::PhaseState) {}
@@ -43,10 +49,14 @@ pub trait ModifierTrait where Self: Clone + Sized{
     fn on_game_start(self, _game: &mut Game) {}
     fn on_any_death(self, _game: &mut Game, _player: crate::game::player::PlayerReference) {}
     fn before_initial_role_creation(self, _game: &mut Game) {}
+    fn vote_weight(self, _game: &Game, _voter: crate::game::player::PlayerReference, _vote_type: VoteType, weight: u8) -> u8 {weight}
+    fn can_whisper(self, _game: &Game, _whisperer: crate::game::player::PlayerReference, _whisperee: crate::game::player::PlayerReference, allowed: bool) -> bool {allowed}
 }
 
+/// Also used as the lobby setting for an enabled modifier, so any parameters the host can edit are fields of these structs
 #[enum_delegate::implement(ModifierTrait)]
-#[derive(Clone, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
 pub enum ModifierState{
     ObscuredGraves(ObscuredGraves),
     RandomLoveLinks(RandomLoveLinks),
@@ -61,6 +71,8 @@ pub enum ModifierState{
     NoNightChat(NoNightChat),
     NoChat(NoChat),
     ScheduledNominations(ScheduledNominations),
+    PluralityElimination(PluralityElimination),
+    SecretBallot(SecretBallot),
 }
 #[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Debug, Hash)]
 #[serde(rename_all = "camelCase")]
@@ -78,6 +90,8 @@ pub enum ModifierType{
     NoNightChat,
     NoChat,
     ScheduledNominations,
+    PluralityElimination,
+    SecretBallot,
 }
 impl ModifierType{
     pub fn default_state(&self)->ModifierState{
@@ -95,6 +109,23 @@ impl ModifierType{
             Self::NoNightChat => ModifierState::NoNightChat(NoNightChat::default()),
             Self::NoChat => ModifierState::NoChat(NoChat::default()),
             Self::ScheduledNominations => ModifierState::ScheduledNominations(ScheduledNominations::default()),
+            Self::PluralityElimination => ModifierState::PluralityElimination(PluralityElimination::default()),
+            Self::SecretBallot => ModifierState::SecretBallot(SecretBallot),
+        }
+    }
+}
+/// Why the host's modifier parameters weren't accepted
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ModifierParametersError{
+    NotEnabled{modifier: ModifierType},
+    InvalidParameters{modifier: ModifierType},
+}
+impl ModifierState{
+    pub fn has_valid_parameters(&self)->bool{
+        match self {
+            Self::TwoThirdsMajority(state) => state.has_valid_parameters(),
+            _ => true
         }
     }
 }
@@ -114,6 +145,8 @@ impl From<&ModifierState> for ModifierType{
             ModifierState::NoNightChat(_) => Self::NoNightChat,
             ModifierState::NoChat(_) => Self::NoChat,
             ModifierState::ScheduledNominations(_) => Self::ScheduledNominations,
+            ModifierState::PluralityElimination(_) => Self::PluralityElimination,
+            ModifierState::SecretBallot(_) => Self::SecretBallot,
         }
     }
 }
@@ -150,9 +183,7 @@ impl Modifiers{
             state
         );
     }
-    pub fn default_from_settings(modifiers: VecSet<ModifierType>)->Self{
-        let modifiers = modifiers
-            .into_iter().map(|m|{let state = m.default_state(); (m, state)}).collect();
+    pub fn from_settings(modifiers: VecMap<ModifierType, ModifierState>)->Self{
         Self{
             modifiers,
         }
@@ -167,6 +198,18 @@ impl Modifiers{
             modifier.1.on_ability_input_received(game, actor_ref, input.clone());
         }
     }
+    pub fn on_validated_ability_input_received(game: &mut Game, actor_ref: crate::game::player::PlayerReference, input: crate::game::ability_input::AbilityInput){
+        for modifier in game.modifiers.modifiers.clone(){
+            modifier.1.on_validated_ability_input_received(game, actor_ref, input.clone());
+        }
+    }
+    pub fn controller_parameters_map(game: &Game)->crate::game::ability_input::ControllerParametersMap{
+        let mut out = crate::game::ability_input::ControllerParametersMap::default();
+        for modifier in game.modifiers.modifiers.clone(){
+            out.combine_overwrite(modifier.1.controller_parameters_map(game));
+        }
+        out
+    }
     pub fn on_grave_added(game: &mut Game, event: GraveReference){
         for modifier in game.modifiers.modifiers.clone(){
             modifier.1.on_grave_added(game, event);
@@ -197,4 +240,16 @@ impl Modifiers{
             modifier.1.before_initial_role_creation(game);
         }
     }
+    pub fn vote_weight(game: &Game, voter: crate::game::player::PlayerReference, vote_type: VoteType, mut weight: u8)->u8{
+        for modifier in game.modifiers.modifiers.clone(){
+            weight = modifier.1.vote_weight(game, voter, vote_type, weight);
+        }
+        weight
+    }
+    pub fn can_whisper(game: &Game, whisperer: crate::game::player::PlayerReference, whisperee: crate::game::player::PlayerReference, mut allowed: bool)->bool{
+        for modifier in game.modifiers.modifiers.clone(){
+            allowed = modifier.1.can_whisper(game, whisperer, whisperee, allowed);
+        }
+        allowed
+    }
 }
\ No newline at end of file
@@ -1,8 +1,10 @@
+use serde::{Deserialize, Serialize};
+
 use crate::game::{player::PlayerReference, verdict::Verdict};
 
 use super::{ModifierTrait, ModifierType};
 
-#[derive(Clone, Default, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct NoAbstaining;
 
 /*
@@ -1,6 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+use crate::game::{player::PlayerReference, Game};
+
 use super::{ModifierTrait, ModifierType};
 
-#[derive(Clone, Default, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct NoWhispers;
 
 impl From<&NoWhispers> for ModifierType{
@@ -9,4 +13,8 @@ impl From<&NoWhispers> for ModifierType{
     }
 }
 
-impl ModifierTrait for NoWhispers {}
+impl ModifierTrait for NoWhispers {
+    fn can_whisper(self, _game: &Game, _whisperer: PlayerReference, _whisperee: PlayerReference, _allowed: bool) -> bool {
+        false
+    }
+}
@@ -1,8 +1,10 @@
+use serde::{Deserialize, Serialize};
+
 use crate::game::{grave::{GraveInformation, GraveReference}, Game};
 
 use super::{ModifierTrait, ModifierType};
 
-#[derive(Clone, Default, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct ObscuredGraves;
 
 impl From<&ObscuredGraves> for ModifierType{
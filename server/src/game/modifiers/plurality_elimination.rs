@@ -0,0 +1,181 @@
+use rand::seq::IteratorRandom;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    game::{
+        ability_input::*,
+        chat::{ChatGroup, ChatMessageVariant},
+        phase::{PhaseState, PhaseStateMachine, PhaseType},
+        player::PlayerReference,
+        vote_weight::{VoteType, VoteWeight},
+        Game
+    },
+    packet::ToClientPacket, vec_map::VecMap, vec_set::{vec_set, VecSet}
+};
+
+use super::{ModifierState, ModifierTrait, ModifierType, Modifiers};
+
+/// Replaces trials with a single plurality vote.
+/// Every living player votes through their elimination vote controller during nomination,
+/// and the player with the most votes at the end of nomination is eliminated.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluralityElimination{
+    /// What happens when the most voted players are tied
+    #[serde(default)]
+    pub tie_break: EliminationTieBreak,
+    /// Only these players can be voted for, set when a tie causes a runoff
+    #[serde(skip)]
+    runoff_candidates: VecSet<PlayerReference>,
+}
+
+#[derive(Clone, Copy, Default, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum EliminationTieBreak{
+    #[default]
+    NoElimination,
+    Random,
+    Runoff
+}
+
+impl From<&PluralityElimination> for ModifierType{
+    fn from(_: &PluralityElimination) -> Self {
+        ModifierType::PluralityElimination
+    }
+}
+
+impl ModifierTrait for PluralityElimination{
+    fn controller_parameters_map(self, game: &Game) -> ControllerParametersMap {
+        let mut out = ControllerParametersMap::default();
+
+        let available_players: VecSet<PlayerReference> = PlayerReference::all_players(game)
+            .filter(|p| p.alive(game))
+            .filter(|p| self.runoff_candidates.is_empty() || self.runoff_candidates.contains(p))
+            .collect();
+
+        for player in PlayerReference::all_players(game){
+            out.combine_overwrite(
+                ControllerParametersMap::new_controller_fast(
+                    game,
+                    ControllerID::elimination_vote(player),
+                    AvailableAbilitySelection::new_player_list(
+                        available_players.iter().filter(|p| **p != player).copied().collect(),
+                        false,
+                        Some(1)
+                    ),
+                    AbilitySelection::new_player_list(vec![]),
+                    !player.alive(game) || game.current_phase().phase() != PhaseType::Nomination,
+                    Some(PhaseType::Nomination),
+                    false,
+                    vec_set![player]
+                )
+            );
+        }
+
+        out
+    }
+    fn on_validated_ability_input_received(self, game: &mut Game, actor_ref: PlayerReference, input: AbilityInput) {
+        if input.id() != ControllerID::elimination_vote(actor_ref) {return}
+
+        game.send_packet_to_all(ToClientPacket::PlayerVotes {
+            votes_for_player: PlayerReference::ref_vec_map_to_index(Self::votes(game))
+        });
+    }
+    fn on_phase_start(self, game: &mut Game, phase: PhaseState) {
+        if matches!(phase, PhaseState::Nomination { .. }) || self.runoff_candidates.is_empty() {return}
+
+        Modifiers::set_modifier(game, ModifierState::PluralityElimination(Self::new(self.tie_break)));
+    }
+}
+
+impl PluralityElimination{
+    pub fn new(tie_break: EliminationTieBreak)->Self{
+        Self { tie_break, runoff_candidates: VecSet::new() }
+    }
+    fn get(game: &Game)->Self{
+        match Modifiers::get_modifier(game, ModifierType::PluralityElimination) {
+            Some(ModifierState::PluralityElimination(state)) => state.clone(),
+            _ => Self::default()
+        }
+    }
+
+    /// The weighted number of elimination votes each player has received from living players
+    pub fn votes(game: &Game)->VecMap<PlayerReference, u8>{
+        let runoff_candidates = Self::get(game).runoff_candidates;
+        let mut votes: VecMap<PlayerReference, u8> = VecMap::new();
+
+        for voter in PlayerReference::all_players(game){
+            if !voter.alive(game) {continue}
+            let Some(PlayerListSelection(target)) = game.saved_controllers
+                .get_controller_current_selection_player_list(ControllerID::elimination_vote(voter))
+                else {continue};
+            let Some(target) = target.first().copied() else {continue};
+            if !target.alive(game) || (!runoff_candidates.is_empty() && !runoff_candidates.contains(&target)) {continue}
+
+            let weight = VoteWeight::get(game, voter, VoteType::Nomination);
+
+            if let Some(num_votes) = votes.get_mut(&target) {
+                *num_votes = num_votes.saturating_add(weight);
+            } else {
+                votes.insert(target, weight);
+            }
+        }
+
+        votes
+    }
+
+    /// Returns what phase should come after nomination
+    pub fn on_nomination_end(game: &mut Game, trials_left: u8)->PhaseState{
+        let votes = Self::votes(game);
+
+        let Some(maximum_votes) = votes.values().max().copied() else {
+            game.add_message_to_chat_group(ChatGroup::All, ChatMessageVariant::EliminationResult {
+                eliminated: None, tied_players: Vec::new(), votes: 0
+            });
+            return PhaseState::Dusk;
+        };
+
+        let tied_players: VecSet<PlayerReference> = votes.iter()
+            .filter(|(_, votes)| **votes == maximum_votes)
+            .map(|(player, _)| *player)
+            .collect();
+        let tied_player_indices: Vec<_> = tied_players.iter().map(PlayerReference::index).collect();
+
+        let eliminated = if tied_players.len() == 1 {
+            tied_players.iter().next().copied()
+        } else {
+            match Self::get(game).tie_break {
+                EliminationTieBreak::NoElimination => None,
+                EliminationTieBreak::Random => tied_players.iter().choose(&mut rand::rng()).copied(),
+                EliminationTieBreak::Runoff => {
+                    if Self::get(game).runoff_candidates.is_empty() {
+                        game.add_message_to_chat_group(ChatGroup::All, ChatMessageVariant::EliminationRunoff {
+                            tied_players: tied_player_indices
+                        });
+                        Modifiers::set_modifier(game, ModifierState::PluralityElimination(Self{
+                            runoff_candidates: tied_players,
+                            ..Self::get(game)
+                        }));
+
+                        return PhaseState::Nomination {
+                            trials_left,
+                            nomination_time_remaining: PhaseStateMachine::get_phase_time_length(game, PhaseType::Nomination)
+                        };
+                    }
+                    None
+                },
+            }
+        };
+
+        game.add_message_to_chat_group(ChatGroup::All, ChatMessageVariant::EliminationResult {
+            eliminated: eliminated.map(|p| p.index()),
+            tied_players: tied_player_indices,
+            votes: maximum_votes
+        });
+
+        match eliminated {
+            Some(player_on_trial) => PhaseState::FinalWords { player_on_trial },
+            None => PhaseState::Dusk,
+        }
+    }
+}
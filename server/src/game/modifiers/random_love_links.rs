@@ -1,11 +1,16 @@
-use rand::seq::IteratorRandom;
+use rand::seq::{IteratorRandom, SliceRandom};
+use serde::{Deserialize, Serialize};
 
 use crate::game::{components::love_linked::LoveLinked, player::PlayerReference, Game};
 
 use super::{ModifierTrait, ModifierType};
 
-#[derive(Clone, Default, PartialEq, Eq, Hash)]
-pub struct RandomLoveLinks;
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RandomLoveLinks{
+    /// How many pairs to link, everyone is linked if this is None
+    pub pairs: Option<u8>,
+}
 
 impl From<&RandomLoveLinks> for ModifierType{
     fn from(_: &RandomLoveLinks) -> Self {
@@ -15,7 +20,12 @@ impl From<&RandomLoveLinks> for ModifierType{
 
 impl ModifierTrait for RandomLoveLinks{
     fn on_game_start(self, game: &mut Game) {
-        for player in PlayerReference::all_players(game) {
+        let mut players: Vec<PlayerReference> = PlayerReference::all_players(game).collect();
+        players.shuffle(&mut rand::rng());
+
+        let mut pairs_linked: u8 = 0;
+        for player in players {
+            if self.pairs.is_some_and(|pairs| pairs_linked >= pairs) {break;}
             if LoveLinked::get_links(game, player).len() != 0 {continue;}
 
             let random_unlinked_player = PlayerReference::all_players(game)
@@ -25,7 +35,8 @@ impl ModifierTrait for RandomLoveLinks{
 
             if let Some(other_player) = random_unlinked_player {
                 LoveLinked::add_love_link(game, player, other_player);
-            }else{
+                pairs_linked = pairs_linked.saturating_add(1);
+            }else if self.pairs.is_none() {
                 let random_player = PlayerReference::all_players(game)
                     .filter(|p| *p != player)
                     .choose(&mut rand::rng());
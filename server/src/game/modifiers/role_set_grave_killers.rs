@@ -1,9 +1,16 @@
-use crate::game::{grave::{GraveDeathCause, GraveInformation, GraveKiller, GraveReference}, role_list::RoleSet, Game};
+use serde::{Deserialize, Serialize};
+
+use crate::game::{grave::{GraveDeathCause, GraveInformation, GraveKiller, GraveReference}, role::Role, role_list::RoleSet, Game};
 
 use super::{ModifierTrait, ModifierType};
 
-#[derive(Clone, Default, PartialEq, Eq, Hash)]
-pub struct RoleSetGraveKillers;
+/// Graves show the killer's role set instead of their role
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RoleSetGraveKillers{
+    /// Names of the host's custom role sets to show, checked in order before the usual role sets
+    pub custom_role_sets: Vec<String>,
+}
 
 impl From<&RoleSetGraveKillers> for ModifierType{
     fn from(_: &RoleSetGraveKillers) -> Self {
@@ -21,20 +28,7 @@ impl ModifierTrait for RoleSetGraveKillers{
                     for killer in killers {
                         new_killers.push(
                             if let GraveKiller::Role(killer_role) = killer {
-                                let killer_role_set = [
-                                    RoleSet::Town,
-                                    RoleSet::Mafia,
-                                    RoleSet::Cult,
-                                    RoleSet::Fiends,
-                                    RoleSet::Minions,
-                                    RoleSet::Neutral,
-                                ].iter().find(|set| set.get_roles().contains(&killer_role));
-    
-                                if let Some(role_set) = killer_role_set {
-                                    GraveKiller::RoleSet(role_set.clone())
-                                } else {
-                                    killer
-                                }
+                                self.killer_role_set(game, killer_role).unwrap_or(killer)
                             } else {
                                 killer
                             }
@@ -52,3 +46,25 @@ impl ModifierTrait for RoleSetGraveKillers{
         }
     }
 }
+impl RoleSetGraveKillers{
+    fn killer_role_set(&self, game: &Game, killer_role: Role) -> Option<GraveKiller> {
+        let custom_role_set = self.custom_role_sets.iter()
+            .filter_map(|name| game.settings.custom_role_sets.iter().find(|set| set.name == *name))
+            .find(|set| set.roles.contains(&killer_role));
+
+        if let Some(custom_role_set) = custom_role_set {
+            return Some(GraveKiller::CustomRoleSet(custom_role_set.name.clone()));
+        }
+
+        [
+            RoleSet::Town,
+            RoleSet::Mafia,
+            RoleSet::Cult,
+            RoleSet::Fiends,
+            RoleSet::Minions,
+            RoleSet::Neutral,
+        ].into_iter()
+            .find(|set| set.get_roles().contains(&killer_role))
+            .map(GraveKiller::RoleSet)
+    }
+}
@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+
+use super::{ModifierTrait, ModifierType};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SecretBallot;
+
+/*
+    There is modifier specific code in the set_chosen_vote() function
+    and in phase.rs for the Testimony and Judgement phases
+*/
+impl From<&SecretBallot> for ModifierType{
+    fn from(_: &SecretBallot) -> Self {
+        ModifierType::SecretBallot
+    }
+}
+
+impl ModifierTrait for SecretBallot{}
@@ -1,7 +1,21 @@
-use super::{ModifierTrait, ModifierType};
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Default, PartialEq, Eq, Hash)]
-pub struct TwoThirdsMajority;
+use crate::game::Game;
+
+use super::{ModifierState, ModifierTrait, ModifierType, Modifiers};
+
+/// Trials need a larger majority than usual, two thirds unless the host picks another fraction
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TwoThirdsMajority{
+    pub numerator: u8,
+    pub denominator: u8,
+}
+impl Default for TwoThirdsMajority{
+    fn default() -> Self {
+        Self { numerator: 2, denominator: 3 }
+    }
+}
 
 impl From<&TwoThirdsMajority> for ModifierType{
     fn from(_: &TwoThirdsMajority) -> Self {
@@ -10,3 +24,27 @@ impl From<&TwoThirdsMajority> for ModifierType{
 }
 
 impl ModifierTrait for TwoThirdsMajority {}
+
+impl TwoThirdsMajority{
+    pub fn get(game: &Game)->Option<&Self>{
+        match Modifiers::get_modifier(game, ModifierType::TwoThirdsMajority) {
+            Some(ModifierState::TwoThirdsMajority(state)) => Some(state),
+            _ => None
+        }
+    }
+    /// A fraction of 1 or more would hang every player put on trial, even with no guilty votes
+    pub fn has_valid_parameters(&self)->bool{
+        self.numerator > 0 && self.numerator < self.denominator
+    }
+    /// The fraction of eligible voters, rounded up
+    pub fn nomination_votes_required(&self, eligible_voters: u8)->u8{
+        let numerator = self.numerator as u16;
+        let denominator = self.denominator.max(1) as u16;
+        ((eligible_voters as u16 * numerator).div_ceil(denominator)) as u8
+    }
+    pub fn verdict_is_guilty(&self, guilty: u8, innocent: u8)->bool{
+        let numerator = self.numerator as u16;
+        let denominator = self.denominator as u16;
+        innocent as u16 * denominator.saturating_sub(numerator) <= guilty as u16 * numerator
+    }
+}
@@ -1,26 +1,45 @@
-use crate::{log, packet::ToServerPacket, strings::TidyableString};
+use crate::{log, packet::{ToClientPacket, ToServerPacket}, strings::TidyableString};
 
 use super::{
-    chat::{ChatGroup, ChatMessageVariant, MessageSender}, event::on_fast_forward::OnFastForward, modifiers::{ModifierType, Modifiers}, phase::{PhaseState, PhaseType}, player::{PlayerIndex, PlayerReference}, role::{
-        mayor::Mayor, politician::Politician,
-        Role, RoleState
-    }, spectator::spectator_pointer::{SpectatorIndex, SpectatorPointer}, Game
+    chat::{ChatGroup, ChatMessage, ChatMessageVariant, MessageSender}, event::on_fast_forward::OnFastForward, modifiers::{ModifierType, Modifiers}, phase::{PhaseState, PhaseType}, player::{PlayerIndex, PlayerReference}, role::{Role, RoleState}, spectator::spectator_pointer::{SpectatorID, SpectatorPointer}, whisper::Whispers, Game
 };
 
 
 
 
 impl Game {
-    pub fn on_spectator_message(&mut self, sender_index: SpectatorIndex, incoming_packet: ToServerPacket){
-        let sender_pointer = SpectatorPointer::new(sender_index);
+    pub fn on_spectator_message(&mut self, sender_id: SpectatorID, incoming_packet: ToServerPacket){
+        let sender_pointer = SpectatorPointer::new(sender_id);
 
-        #[allow(clippy::single_match)]
         match incoming_packet {
             ToServerPacket::VoteFastForwardPhase { fast_forward } => {
                 if sender_pointer.host(self) && fast_forward && !self.phase_machine.time_remaining.is_zero(){
                     OnFastForward::invoke(self);
                 }
             },
+            ToServerPacket::SendChatMessage { text, block, reply_to, mentions } => {
+                if text.replace(['\n', '\r'], "").trim().is_empty() {
+                    return;
+                }
+                let reply_to = reply_to.filter(|id| self.spectator_chat_messages.iter().any(|message| message.id == *id));
+                let mentions = self.valid_mentions(mentions);
+                let Some(text) = self.settings.chat_filter.apply(&text) else {
+                    let message = ChatMessage::new_private(self.next_chat_message_id(), ChatMessageVariant::BlockedByChatFilter);
+                    sender_pointer.send_packet(self, ToClientPacket::AddChatMessages { chat_messages: vec![message] });
+                    return;
+                };
+
+                self.add_message_to_chat_group(
+                    ChatGroup::Spectator,
+                    ChatMessageVariant::Normal{
+                        message_sender: MessageSender::Spectator { spectator: sender_id },
+                        text: text.trim_newline().trim_whitespace().truncate(600).truncate_lines(35),
+                        block,
+                        reply_to,
+                        mentions
+                    }
+                );
+            },
             _ => {
             }
         }
@@ -38,6 +57,7 @@ impl Game {
         'packet_match: {match incoming_packet {
             ToServerPacket::Vote { player_index: player_voted_index } => {
                 let &PhaseState::Nomination { .. } = self.current_phase() else {break 'packet_match};
+                if Modifiers::modifier_is_enabled(self, ModifierType::PluralityElimination) {break 'packet_match}
 
                 let player_voted_ref = match PlayerReference::index_option_to_ref(self, &player_voted_index){
                     Ok(player_voted_ref) => player_voted_ref,
@@ -55,10 +75,16 @@ impl Game {
                 
                 sender_player_ref.set_verdict(self, verdict);
             },
-            ToServerPacket::SendChatMessage { text, block } => {
+            ToServerPacket::SendChatMessage { text, block, reply_to, mentions } => {
                 if text.replace(['\n', '\r'], "").trim().is_empty() {
                     break 'packet_match;
                 }
+                let reply_to = reply_to.filter(|id| sender_player_ref.chat_messages(self).iter().any(|message| message.id == *id));
+                let mentions = self.valid_mentions(mentions);
+                let Some(text) = self.settings.chat_filter.apply(&text) else {
+                    sender_player_ref.add_private_chat_message(self, ChatMessageVariant::BlockedByChatFilter);
+                    break 'packet_match;
+                };
                 
                 for chat_group in sender_player_ref.get_current_send_chat_groups(self){
                     let message_sender = match chat_group {
@@ -93,74 +119,23 @@ impl Game {
                         ChatMessageVariant::Normal{
                             message_sender,
                             text: text.trim_newline().trim_whitespace().truncate(600).truncate_lines(35), 
-                            block
+                            block,
+                            reply_to,
+                            mentions: mentions.clone()
                         }
                     );
                 }
             },
             ToServerPacket::SendWhisper { player_index: whispered_to_player_index, text } => {
-                if Modifiers::modifier_is_enabled(self, ModifierType::NoWhispers) {
-                    sender_player_ref.add_private_chat_message(self, ChatMessageVariant::InvalidWhisper);
-                    break 'packet_match
-                }
-
-                let whisperee_ref = match PlayerReference::new(self, whispered_to_player_index){
-                    Ok(whisperee_ref) => whisperee_ref,
-                    Err(_) => {
-                        sender_player_ref.add_private_chat_message(self, ChatMessageVariant::InvalidWhisper);
-                        break 'packet_match
-                    },
-                };
-
-                if !self.current_phase().is_day() || 
-                    whisperee_ref.alive(self) != sender_player_ref.alive(self) ||
-                    whisperee_ref == sender_player_ref || 
-                    !sender_player_ref.get_current_send_chat_groups(self).contains(&ChatGroup::All) ||
-                    text.replace(['\n', '\r'], "").trim().is_empty()
-                {
-                    sender_player_ref.add_private_chat_message(self, ChatMessageVariant::InvalidWhisper);
-                    break 'packet_match;
-                }
-
-                if let RoleState::Mayor(Mayor{revealed: true}) = whisperee_ref.role_state(self) {
-                    sender_player_ref.add_private_chat_message(self, ChatMessageVariant::InvalidWhisper);
-                    break 'packet_match;
-                }
-                if let RoleState::Mayor(Mayor{revealed: true}) = sender_player_ref.role_state(self) {
-                    sender_player_ref.add_private_chat_message(self, ChatMessageVariant::InvalidWhisper);
-                    break 'packet_match;
-                }
-                if let RoleState::Politician(Politician{revealed: true, ..}) = whisperee_ref.role_state(self) {
-                    sender_player_ref.add_private_chat_message(self, ChatMessageVariant::InvalidWhisper);
-                    break 'packet_match;
-                }
-                if let RoleState::Politician(Politician{revealed: true, ..}) = sender_player_ref.role_state(self) {
-                    sender_player_ref.add_private_chat_message(self, ChatMessageVariant::InvalidWhisper);
-                    break 'packet_match;
-                }
-
-
-                self.add_message_to_chat_group(ChatGroup::All, ChatMessageVariant::BroadcastWhisper { whisperer: sender_player_index, whisperee: whispered_to_player_index });
-                let message = ChatMessageVariant::Whisper { 
-                    from_player_index: sender_player_index, 
-                    to_player_index: whispered_to_player_index, 
-                    text 
-                };
-        
-                sender_player_ref.add_private_chat_message(self, message.clone());
-
-                for player in PlayerReference::all_players(self){
-                    if 
-                        player.role(self) == Role::Informant ||
-                        whisperee_ref == player
-                    {
-                        player.add_private_chat_message(self, message.clone());
-                    }
-                }
+                Whispers::send(self, sender_player_ref, whispered_to_player_index, text);
             },
             ToServerPacket::SaveWill { will } => {
                 sender_player_ref.set_will(self, will);
             },
+            ToServerPacket::SaveWillTemplate { template } => {
+                let will = template.to_will(self);
+                sender_player_ref.set_will(self, will);
+            },
             ToServerPacket::SaveNotes { notes } => {
                 sender_player_ref.set_notes(self, notes);
             },
@@ -220,4 +195,14 @@ impl Game {
         }
 
     }
-}
\ No newline at end of file
+    /// Keeps mentions of real players, each listed once in the order they were mentioned
+    fn valid_mentions(&self, mentions: Vec<PlayerIndex>) -> Vec<PlayerIndex> {
+        let mut out: Vec<PlayerIndex> = Vec::new();
+        for player_ref in mentions.into_iter().filter_map(|index| PlayerReference::new(self, index).ok()) {
+            if !out.contains(&player_ref.index()) {
+                out.push(player_ref.index());
+            }
+        }
+        out
+    }
+}
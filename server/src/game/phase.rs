@@ -2,10 +2,11 @@ use std::{ops::DivAssign, time::Duration};
 
 use serde::{Serialize, Deserialize};
 
-use crate::{game::modifiers::{ModifierType, Modifiers}, packet::ToClientPacket};
+use crate::{game::modifiers::{plurality_elimination::PluralityElimination, two_thirds_majority::TwoThirdsMajority, ModifierType, Modifiers}, packet::ToClientPacket};
 
 use super::{
     chat::{ChatGroup, ChatMessageVariant},
+    components::{game_history::{GameHistory, GameHistoryEventKind}, night_resolution_trace::NightResolutionTrace, nomination_ties::NominationTies},
     event::{
         before_phase_end::BeforePhaseEnd, on_any_death::OnAnyDeath,
         on_night_priority::OnNightPriority, on_phase_start::OnPhaseStart
@@ -171,13 +172,18 @@ impl PhaseState {
                 game.send_packet_to_all(packet);
             },
             PhaseState::Testimony { player_on_trial, .. } => {
+                let players_voted = if Modifiers::modifier_is_enabled(game, ModifierType::SecretBallot) {
+                    Vec::new()
+                } else {
+                    PlayerReference::all_players(game)
+                        .filter(|player_ref| player_ref.chosen_vote(game) == Some(player_on_trial))
+                        .map(|player_ref| player_ref.index())
+                        .collect()
+                };
                 game.add_message_to_chat_group(ChatGroup::All, 
                     ChatMessageVariant::PlayerNominated {
                         player_index: player_on_trial.index(),
-                        players_voted: PlayerReference::all_players(game)
-                            .filter(|player_ref| player_ref.chosen_vote(game) == Some(player_on_trial))
-                            .map(|player_ref| player_ref.index())
-                            .collect()
+                        players_voted
                     }
                 );
                 game.send_packet_to_all(ToClientPacket::PlayerOnTrial { player_index: player_on_trial.index() });
@@ -211,9 +217,12 @@ impl PhaseState {
             PhaseState::Nomination {trials_left, ..} => {
 
 
-                if Modifiers::modifier_is_enabled(game, ModifierType::ScheduledNominations){
+                if Modifiers::modifier_is_enabled(game, ModifierType::PluralityElimination){
+                    PluralityElimination::on_nomination_end(game, trials_left)
+                }else if Modifiers::modifier_is_enabled(game, ModifierType::ScheduledNominations){
                     
-                    if let Some(player_on_trial) = game.count_nomination_and_start_trial(false){    
+                    let nominated_players = game.count_nomination();
+                    if let Some(player_on_trial) = NominationTies::player_on_trial(game, &nominated_players){    
 
                         game.send_packet_to_all(ToClientPacket::PlayerOnTrial { player_index: player_on_trial.index() } );
     
@@ -222,6 +231,8 @@ impl PhaseState {
                             player_on_trial, 
                             nomination_time_remaining: PhaseStateMachine::get_phase_time_length(game, PhaseType::Nomination)
                         }
+                    }else if let Some(revote) = NominationTies::revote(game, trials_left) {
+                        revote
                     }else if trials_left > 1  {
                         Self::Nomination {
                             trials_left: trials_left.saturating_sub(1),
@@ -231,6 +242,8 @@ impl PhaseState {
                         Self::Dusk
                     }
 
+                }else if let Some(revote) = NominationTies::revote(game, trials_left) {
+                    revote
                 }else{
                     Self::Dusk
                 }
@@ -240,19 +253,27 @@ impl PhaseState {
             },
             PhaseState::Judgement { trials_left, player_on_trial, nomination_time_remaining } => {
 
-                game.add_messages_to_chat_group(ChatGroup::All, 
-                PlayerReference::all_players(game)
+                let verdict_messages: Vec<(PlayerReference, ChatMessageVariant)> = PlayerReference::all_players(game)
                     .filter(|player_ref|{
                         player_ref.alive(game) && *player_ref != player_on_trial
                     })
                     .map(|player_ref|
-                        ChatMessageVariant::JudgementVerdict{
+                        (player_ref, ChatMessageVariant::JudgementVerdict{
                             voter_player_index: player_ref.index(),
                             verdict: player_ref.verdict(game)
-                        }
+                        })
                     )
-                    .collect()
-                );
+                    .collect();
+
+                if Modifiers::modifier_is_enabled(game, ModifierType::SecretBallot) {
+                    for (player_ref, message) in verdict_messages {
+                        player_ref.add_private_chat_message(game, message);
+                    }
+                } else {
+                    game.add_messages_to_chat_group(ChatGroup::All, 
+                        verdict_messages.into_iter().map(|(_, message)| message).collect()
+                    );
+                }
                 
                 let (guilty, innocent) = game.count_verdict_votes(player_on_trial);
                 game.add_message_to_chat_group(ChatGroup::All, ChatMessageVariant::TrialVerdict{ 
@@ -260,11 +281,17 @@ impl PhaseState {
                     innocent, guilty 
                 });
 
-                let hang = if Modifiers::modifier_is_enabled(game, ModifierType::TwoThirdsMajority) {
-                    innocent <= 2 * guilty
+                let hang = if let Some(majority) = TwoThirdsMajority::get(game) {
+                    majority.verdict_is_guilty(guilty, innocent)
                 } else {
                     innocent < guilty
                 };
+
+                let verdicts = PlayerReference::all_players(game)
+                    .filter(|player_ref| player_ref.alive(game) && *player_ref != player_on_trial)
+                    .map(|player_ref| (player_ref, player_ref.verdict(game)))
+                    .collect();
+                GameHistory::record(game, GameHistoryEventKind::TrialResult { player_on_trial, guilty, innocent, verdicts, voted_guilty: hang });
                 
                 if hang {
                     Self::FinalWords { player_on_trial }
@@ -291,6 +318,7 @@ impl PhaseState {
                     let visits = player_ref.convert_selection_to_visits(game);
                     player_ref.set_night_visits(game, visits.clone());
                 }
+                NightResolutionTrace::on_night_resolution_start(game);
 
                 for priority in Priority::values(){
                     OnNightPriority::new(priority).invoke(game);
@@ -298,6 +326,7 @@ impl PhaseState {
                         player_ref.do_night_action(game, priority);
                     }
                 }
+                NightResolutionTrace::on_night_resolution_end(game);
 
                 for player_ref in PlayerReference::all_players(game){
                     player_ref.push_night_messages_to_player(game);
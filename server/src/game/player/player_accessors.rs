@@ -3,10 +3,10 @@ use vec1::Vec1;
 use crate::{
     game::{
         attack_power::DefensePower, chat::{
-            ChatGroup, ChatMessage, ChatMessageVariant
-        }, event::{on_convert::OnConvert, on_fast_forward::OnFastForward, on_remove_role_label::OnRemoveRoleLabel}, grave::GraveKiller, modifiers::{ModifierType, Modifiers}, role::{Role, RoleState}, tag::Tag, verdict::Verdict, visit::Visit, win_condition::WinCondition, Game
+            ChatGroup, ChatMessage, ChatMessageID, ChatMessageVariant
+        }, components::{nomination_ties::NominationTies, will_history::{WillAuthor, WillHistory}}, event::{on_convert::OnConvert, on_fast_forward::OnFastForward, on_remove_role_label::OnRemoveRoleLabel}, grave::GraveKiller, modifiers::{ModifierType, Modifiers}, role::{Role, RoleState}, tag::Tag, verdict::Verdict, visit::Visit, win_condition::WinCondition, Game
     }, 
-    packet::ToClientPacket, vec_map::VecMap, vec_set::VecSet, 
+    packet::ToClientPacket, strings::TidyableString, vec_map::VecMap, vec_set::VecSet, 
 };
 use super::PlayerReference;
 
@@ -49,6 +49,8 @@ impl PlayerReference{
         &self.deref(game).will
     }
     pub fn set_will(&self, game: &mut Game, will: String){
+        let will = will.truncate(WillHistory::MAX_WILL_LENGTH);
+        WillHistory::record(game, *self, WillAuthor::Player, will.clone());
         self.deref_mut(game).will = will;
         self.send_packet(game, ToClientPacket::YourWill { will: self.deref(game).will.clone() });
     }
@@ -169,9 +171,7 @@ impl PlayerReference{
     }
 
     pub fn add_private_chat_message(&self, game: &mut Game, message: ChatMessageVariant) {
-        let message = ChatMessage::new_private(message);
-
-        self.add_chat_message(game, message.clone());
+        game.add_private_message_to_players([*self], message);
     }
     pub fn add_private_chat_messages(&self, game: &mut Game, messages: Vec<ChatMessageVariant>){
         for message in messages.into_iter(){
@@ -182,6 +182,15 @@ impl PlayerReference{
         self.deref_mut(game).chat_messages.push(message.clone());
         self.deref_mut(game).queued_chat_messages.push(message);
     }
+    /// Returns whether the player had the message
+    pub fn remove_chat_message(&self, game: &mut Game, id: ChatMessageID) -> bool {
+        let player = self.deref_mut(game);
+        player.queued_chat_messages.retain(|message| message.id != id);
+
+        let had_message = player.chat_messages.iter().any(|message| message.id == id);
+        player.chat_messages.retain(|message| message.id != id);
+        had_message
+    }
     pub fn chat_messages<'a>(&self, game: &'a Game) -> &'a Vec<ChatMessage> {
         &self.deref(game).chat_messages
     }
@@ -229,7 +238,7 @@ impl PlayerReference{
         }
         
         if let Some(chosen_vote) = chosen_vote {
-            if chosen_vote == *self || !chosen_vote.deref(game).alive {
+            if chosen_vote == *self || !chosen_vote.deref(game).alive || !NominationTies::can_be_voted_for(game, chosen_vote) {
                 self.deref_mut(game).voting_variables.chosen_vote = None;
                 self.send_packet(game, ToClientPacket::YourVoting { 
                     player_index: None
@@ -246,10 +255,15 @@ impl PlayerReference{
         game.send_packet_to_all(player_votes_packet);
         
         if send_chat_message {
-            game.add_message_to_chat_group(ChatGroup::All, ChatMessageVariant::Voted{
+            let message = ChatMessageVariant::Voted{
                 voter: self.index(), 
                 votee: chosen_vote.as_ref().map(PlayerReference::index)
-            });
+            };
+            if Modifiers::modifier_is_enabled(game, ModifierType::SecretBallot) {
+                self.add_private_chat_message(game, message);
+            }else{
+                game.add_message_to_chat_group(ChatGroup::All, message);
+            }
         }
         
         true
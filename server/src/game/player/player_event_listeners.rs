@@ -1,4 +1,4 @@
-use crate::game::{ability_input::{AbilityInput, ControllerID}, grave::GraveReference, role::RoleState, Game};
+use crate::game::{ability_input::{AbilityInput, ControllerID}, grave::GraveReference, role::RoleState, vote_weight::VoteType, Game};
 
 use super::PlayerReference;
 
@@ -30,4 +30,13 @@ impl PlayerReference {
     pub fn before_initial_role_creation(&self, game: &mut Game){
         self.role_state(game).clone().before_initial_role_creation(game, *self)
     }
+    pub fn vote_weight(&self, game: &Game, voter: PlayerReference, vote_type: VoteType, weight: u8) -> u8 {
+        self.role_state(game).clone().vote_weight(game, *self, voter, vote_type, weight)
+    }
+    pub fn can_whisper(&self, game: &Game, whisperer: PlayerReference, whisperee: PlayerReference, allowed: bool) -> bool {
+        self.role_state(game).clone().can_whisper(game, *self, whisperer, whisperee, allowed)
+    }
+    pub fn reads_whisper(&self, game: &Game, whisperer: PlayerReference, whisperee: PlayerReference) -> bool {
+        self.role_state(game).clone().reads_whisper(game, *self, whisperer, whisperee)
+    }
 }
\ No newline at end of file
@@ -8,7 +8,9 @@ use crate::{game::{
     components::{
         arsonist_doused::ArsonistDoused,
         drunk_aura::DrunkAura,
-        insider_group::InsiderGroupID
+        insider_group::InsiderGroupID,
+        muted::Muted,
+        night_resolution_trace::{AttackOutcome, NightResolutionTrace, NightTraceEvent}
     }, event::{
         before_role_switch::BeforeRoleSwitch, on_any_death::OnAnyDeath, on_role_switch::OnRoleSwitch
     }, game_conclusion::GameConclusion, grave::{Grave, GraveKiller}, modifiers::{ModifierType, Modifiers}, phase::PhaseType, role::{chronokaiser::Chronokaiser, Priority, Role, RoleState}, visit::{Visit, VisitTag}, win_condition::WinCondition, Game
@@ -24,6 +26,7 @@ impl PlayerReference{
             self.set_night_roleblocked(game, true);
             self.set_night_visits(game, vec![]);
         }
+        NightResolutionTrace::record(game, NightTraceEvent::Roleblocked { player: *self, immune: roleblock_immune });
 
         if send_messages {
             self.push_night_message(game,
@@ -38,6 +41,7 @@ impl PlayerReference{
                 visitor.set_night_wardblocked(game, true);
                 visitor.set_night_visits(game, vec![]);
                 visitor.push_night_message(game, ChatMessageVariant::Wardblocked);
+                NightResolutionTrace::record(game, NightTraceEvent::Wardblocked { warded: *self, visitor });
                 wardblocked.push(visitor);
             }
         }
@@ -62,7 +66,24 @@ impl PlayerReference{
     pub fn try_night_kill(&self, attacker_refs: &VecSet<PlayerReference>, game: &mut Game, grave_killer: GraveKiller, attack: AttackPower, should_leave_death_note: bool) -> bool {
         self.set_night_attacked(game, true);
 
-        if self.night_defense(game).can_block(attack){
+        let defense = self.night_defense(game);
+        let outcome = if defense.can_block(attack) {
+            AttackOutcome::Blocked
+        } else if self.alive(game) {
+            AttackOutcome::Killed
+        } else {
+            AttackOutcome::AlreadyDead
+        };
+        NightResolutionTrace::record(game, NightTraceEvent::Attacked {
+            target: *self,
+            attackers: attacker_refs.iter().copied().collect(),
+            grave_killer: grave_killer.clone(),
+            attack,
+            defense,
+            outcome
+        });
+
+        if outcome == AttackOutcome::Blocked {
             self.push_night_message(game, ChatMessageVariant::YouSurvivedAttack);
             for attacker in attacker_refs.iter() {
                 attacker.push_night_message(game,ChatMessageVariant::SomeoneSurvivedYourAttack);
@@ -123,6 +144,12 @@ impl PlayerReference{
                 possessed_visit.target.push_night_message(game,
                     ChatMessageVariant::YouWerePossessed { immune: possessed_visit.target.possession_immune(game) }
                 );
+                NightResolutionTrace::record(game, NightTraceEvent::Possessed {
+                    possessor: *self,
+                    target: possessed_visit.target,
+                    possessed_into: possessed_into_visit.target,
+                    immune: possessed_visit.target.possession_immune(game)
+                });
                 if possessed_visit.target.possession_immune(game) {
                     self.push_night_message(game,
                         ChatMessageVariant::TargetIsPossessionImmune
@@ -313,10 +340,13 @@ impl PlayerReference{
         let mut messages = self.night_messages(game).to_vec();
         messages.shuffle(&mut rand::rng());
         messages.sort();
-        self.send_packet(game, ToClientPacket::NightMessages { chat_messages: 
-            messages.iter().map(|msg|ChatMessage::new_private(msg.clone())).collect()
-        });
-        self.add_private_chat_messages(game, messages);
+        let chat_messages: Vec<ChatMessage> = messages.into_iter()
+            .map(|msg|ChatMessage::new_private(game.next_chat_message_id(), msg))
+            .collect();
+        self.send_packet(game, ToClientPacket::NightMessages { chat_messages: chat_messages.clone() });
+        for message in chat_messages {
+            self.add_chat_message(game, message);
+        }
     }
 
     pub fn role_label_map(&self, game: &Game) -> VecMap<PlayerReference, Role> {
@@ -393,11 +423,10 @@ impl PlayerReference{
     /// Mafia kills with MK or gun
     /// Cult kills / converts
     pub fn keeps_game_running(&self, game: &Game) -> bool {
-        if InsiderGroupID::Mafia.is_player_in_revealed_group(game, *self) {return true;}
-        if InsiderGroupID::Cult.is_player_in_revealed_group(game, *self) {return true;}
-        if self.win_condition(game).is_loyalist_for(GameConclusion::Town) {return true;}
-        
-        GameConclusion::keeps_game_running(self.role(game))
+        GameConclusion::player_keeps_game_running(
+            &game.settings, self.role(game), self.win_condition(game),
+            |group| group.is_player_in_revealed_group(game, *self)
+        )
     }
 
     /*
@@ -423,7 +452,11 @@ impl PlayerReference{
         {
             return HashSet::new()
         }
-        self.role_state(game).clone().get_current_send_chat_groups(game, *self)
+        let mut send_chat_groups = self.role_state(game).clone().get_current_send_chat_groups(game, *self);
+        if Muted::is_muted(game, *self) {
+            send_chat_groups.remove(&ChatGroup::All);
+        }
+        send_chat_groups
     }
     pub fn get_current_receive_chat_groups(&self, game: &Game) -> HashSet<ChatGroup> {
         self.role_state(game).clone().get_current_receive_chat_groups(game, *self)
@@ -64,16 +64,15 @@ impl PlayerReference{
             },
             ToClientPacket::EnabledRoles { roles: game.settings.enabled_roles.clone().into_iter().collect() },
             ToClientPacket::RoleList {role_list: game.settings.role_list.clone()},
-            ToClientPacket::EnabledModifiers {
-                modifiers: game.settings.enabled_modifiers.clone().into_iter().collect()
-            },
+            ToClientPacket::new_enabled_modifiers(&game.settings),
+            ToClientPacket::new_modifier_parameters(&game.settings),
             ToClientPacket::PlayerAlive{
                 alive: PlayerReference::all_players(game).map(|p|p.alive(game)).collect()
             }
         ]);
 
         if !game.ticking {
-            self.send_packet(game, ToClientPacket::GameOver { reason: GameOverReason::Draw })
+            self.send_packets(game, game.game_over_packets(GameOverReason::Draw));
         }
 
         if let PhaseState::Testimony { player_on_trial, .. }
@@ -115,13 +115,12 @@ impl Auditor{
     pub fn get_result(game: &Game, chosen_outline: RoleOutlineReference) -> AuditorResult {
         let (role, _) = chosen_outline.deref_as_role_and_player_originally_generated(game);
         
-        let outline = chosen_outline.deref(game);
+        let assignments = chosen_outline.deref(game).get_role_assignments(&game.settings.custom_role_sets);
 
-        if outline.get_role_assignments().len() == 1 || outline.get_role_assignments().len() == 2 {
+        if assignments.len() == 1 || assignments.len() == 2 {
             AuditorResult::One{role}
         }else{
-            let fake_role = outline
-                .get_role_assignments()
+            let fake_role = assignments
                 .into_iter()
                 .map(|data| data.role)
                 .filter(|x|game.settings.enabled_roles.contains(x))
@@ -141,11 +140,10 @@ impl Auditor{
     }
     //panics if chosen_outline is not found
     pub fn get_confused_result(game: &Game, chosen_outline: RoleOutlineReference) -> AuditorResult {        
-        let outline = chosen_outline.deref(game);
+        let assignments = chosen_outline.deref(game).get_role_assignments(&game.settings.custom_role_sets);
 
-        if outline.get_role_assignments().len() == 1 || outline.get_role_assignments().len() == 2 {
-            let fake_role = outline
-                .get_role_assignments()
+        if assignments.len() == 1 || assignments.len() == 2 {
+            let fake_role = assignments
                 .into_iter()
                 .map(|assignment| assignment.role)
                 .filter(|x|game.settings.enabled_roles.contains(x))
@@ -159,8 +157,7 @@ impl Auditor{
                 unreachable!("Auditor role outline is empty")
             }
         }else{
-            let mut fake_roles = outline
-                .get_role_assignments()
+            let mut fake_roles = assignments
                 .into_iter()
                 .map(|assignment| assignment.role)
                 .filter(|x|game.settings.enabled_roles.contains(x))
@@ -46,6 +46,13 @@ pub(super) const DEFENSE: DefensePower = DefensePower::None;
 
 impl RoleStateImpl for Bodyguard {
     type ClientRoleState = ClientRoleState;
+    fn new_state(game: &Game) -> Self {
+        let default = Self::default();
+        Self{
+            self_shields_remaining: game.settings.role_uses(Role::Bodyguard).unwrap_or(default.self_shields_remaining),
+            ..default
+        }
+    }
     fn do_night_action(self, game: &mut Game, actor_ref: PlayerReference, priority: Priority) {
         match priority {
             Priority::Bodyguard => {
@@ -172,6 +172,7 @@ pub(super) fn get_current_send_chat_groups(game: &Game, actor_ref: PlayerReferen
             if InsiderGroupID::Mafia.is_player_in_revealed_group(game, actor_ref) {
                 out.insert(ChatGroup::Mafia);
             }
+            out.extend(custom_insider_chat_groups(game, actor_ref));
 
             //medium
             if PlayerReference::all_players(game)
@@ -273,6 +274,7 @@ pub(super) fn get_current_send_chat_groups(game: &Game, actor_ref: PlayerReferen
                 if InsiderGroupID::Cult.is_player_in_revealed_group(game, actor_ref){
                     night_chat_groups.push(ChatGroup::Cult);
                 }
+                night_chat_groups.extend(custom_insider_chat_groups(game, actor_ref));
                 night_chat_groups
             };
 
@@ -300,6 +302,7 @@ pub(super) fn get_current_receive_chat_groups(game: &Game, actor_ref: PlayerRefe
     if InsiderGroupID::Puppeteer.is_player_in_revealed_group(game, actor_ref){
         out.push(ChatGroup::Puppeteer);
     }
+    out.extend(custom_insider_chat_groups(game, actor_ref));
 
 
     if Detained::is_detained(game, actor_ref) {
@@ -369,6 +372,14 @@ pub(super) fn get_current_receive_chat_groups(game: &Game, actor_ref: PlayerRefe
     out.into_iter().collect()
 }
 
+fn custom_insider_chat_groups(game: &Game, actor_ref: PlayerReference) -> Vec<ChatGroup> {
+    InsiderGroupID::all_insider_groups_with_player(game, actor_ref)
+        .into_iter()
+        .filter(|group| matches!(group, InsiderGroupID::Custom(_)))
+        .map(|group| group.get_insider_chat_group())
+        .collect()
+}
+
 ///Only works for roles that win based on end game condition
 pub(super) fn default_win_condition(role: Role) -> WinCondition {
     if RoleSet::Mafia.get_roles().contains(&role) {
@@ -2,6 +2,7 @@ use serde::Serialize;
 
 use crate::game::attack_power::{AttackPower, DefensePower};
 use crate::game::chat::ChatMessageVariant;
+use crate::game::components::will_history::{WillAuthor, WillHistory};
 use crate::game::grave::GraveKiller;
 use crate::game::phase::PhaseType;
 use crate::game::player::PlayerReference;
@@ -77,6 +78,7 @@ impl RoleStateImpl for Counterfeiter {
                 } else {
                     "".to_owned()
                 };
+                WillHistory::record(game, target_ref, WillAuthor::Role { player: actor_ref, role: Role::Counterfeiter }, fake_alibi.clone());
                 target_ref.set_night_grave_will(game, fake_alibi);
 
                 actor_ref.set_role_state(game, Counterfeiter { 
@@ -34,6 +34,11 @@ pub(super) const DEFENSE: DefensePower = DefensePower::None;
 
 impl RoleStateImpl for Deputy {
     type ClientRoleState = Deputy;
+    fn new_state(game: &Game) -> Self {
+        Self{
+            bullets_remaining: game.settings.role_uses(Role::Deputy).unwrap_or(Self::default().bullets_remaining)
+        }
+    }
     fn on_validated_ability_input_received(self, game: &mut Game, actor_ref: PlayerReference, input_player: PlayerReference, ability_input: super::AbilityInput) {
         
         if actor_ref != input_player {return;}
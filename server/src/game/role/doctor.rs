@@ -37,6 +37,13 @@ pub(super) const DEFENSE: DefensePower = DefensePower::None;
 
 impl RoleStateImpl for Doctor {
     type ClientRoleState = ClientRoleState;
+    fn new_state(game: &Game) -> Self {
+        let default = Self::default();
+        Self{
+            self_heals_remaining: game.settings.role_uses(Role::Doctor).unwrap_or(default.self_heals_remaining),
+            ..default
+        }
+    }
     fn do_night_action(self, game: &mut Game, actor_ref: PlayerReference, priority: Priority) {
         match priority {
             Priority::Heal => {
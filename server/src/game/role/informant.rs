@@ -40,6 +40,9 @@ impl RoleStateImpl for Informant {
             actor_ref.push_night_message(game, message);
         }
     }
+    fn reads_whisper(self, _game: &Game, _actor_ref: PlayerReference, _whisperer: PlayerReference, _whisperee: PlayerReference) -> bool {
+        true
+    }
     fn controller_parameters_map(self, game: &Game, actor_ref: PlayerReference) -> ControllerParametersMap {
         crate::game::role::common_role::controller_parameters_map_player_list_night_typical(
             game,
@@ -42,7 +42,7 @@ impl RoleStateImpl for Jailor {
     type ClientRoleState = Jailor;
     fn new_state(game: &Game) -> Self {
         Self{
-            executions_remaining: game.num_players().div_ceil(5),
+            executions_remaining: game.settings.role_uses(Role::Jailor).unwrap_or(game.num_players().div_ceil(5)),
             ..Self::default()
         }
     }
@@ -61,7 +61,7 @@ impl MafiaKillingWildcard {
         if
             role_can_generate(
                 role, 
-                &game.settings.enabled_roles, 
+                &game.settings, 
                 &PlayerReference::all_players(game)
                     .map(|player_ref| player_ref.role(game))
                     .collect::<Vec<Role>>()
@@ -8,7 +8,7 @@ use crate::game::phase::PhaseType;
 use crate::game::player::PlayerReference;
 
 
-use crate::game::tag::Tag;
+use crate::game::components::enfranchised::Enfranchised;
 use crate::game::Game;
 use crate::vec_set;
 use super::{ControllerID, ControllerParametersMap, GetClientRoleState, Role, RoleStateImpl};
@@ -39,18 +39,17 @@ impl RoleStateImpl for Mayor {
         actor_ref.set_role_state(game, Mayor{
             revealed: true
         });
-        for player in PlayerReference::all_players(game){
-            player.push_player_tag(game, actor_ref, Tag::Enfranchised);
-        }
+        Enfranchised::enfranchise(game, actor_ref);
         game.count_nomination_and_start_trial(
             !Modifiers::modifier_is_enabled(game, crate::game::modifiers::ModifierType::ScheduledNominations)
         );
     }
     fn before_role_switch(self, game: &mut Game, actor_ref: PlayerReference, player: PlayerReference, _new: super::RoleState, _old: super::RoleState) {
         if actor_ref != player {return;}
-        for player in PlayerReference::all_players(game){
-            player.remove_player_tag(game, actor_ref, Tag::Enfranchised);
-        }
+        Enfranchised::unenfranchise(game, actor_ref);
+    }
+    fn can_whisper(self, _game: &Game, actor_ref: PlayerReference, whisperer: PlayerReference, whisperee: PlayerReference, allowed: bool) -> bool {
+        allowed && !(self.revealed && (actor_ref == whisperer || actor_ref == whisperee))
     }
     fn controller_parameters_map(self, game: &Game, actor_ref: PlayerReference) -> ControllerParametersMap {
         ControllerParametersMap::new_controller_fast(
@@ -17,6 +17,7 @@ use super::{
     ability_input::*, 
     components::insider_group::InsiderGroupID, 
     grave::GraveReference, 
+    vote_weight::VoteType,
     win_condition::WinCondition
 };
 
@@ -70,6 +71,18 @@ pub trait RoleStateImpl: Clone + std::fmt::Debug + Default + GetClientRoleState<
     fn on_game_ending(self, _game: &mut Game, _actor_ref: PlayerReference) {}
     fn on_game_start(self, _game: &mut Game, _actor_ref: PlayerReference) {}
     fn before_initial_role_creation(self, _game: &mut Game, _actor_ref: PlayerReference) {}
+    /// Called for every voter, returns the new vote weight of the voter
+    fn vote_weight(self, _game: &Game, _actor_ref: PlayerReference, _voter: PlayerReference, _vote_type: VoteType, weight: u8) -> u8 {
+        weight
+    }
+    /// Called for every whisper, returns whether the whisper is still allowed
+    fn can_whisper(self, _game: &Game, _actor_ref: PlayerReference, _whisperer: PlayerReference, _whisperee: PlayerReference, allowed: bool) -> bool {
+        allowed
+    }
+    /// Called for every whisper, returns whether the actor also reads it
+    fn reads_whisper(self, _game: &Game, _actor_ref: PlayerReference, _whisperer: PlayerReference, _whisperee: PlayerReference) -> bool {
+        false
+    }
 }
 
 // Creates the Role enum
@@ -346,6 +359,21 @@ mod macros {
                         $(Self::$name(role_struct) => role_struct.before_initial_role_creation(game, actor_ref)),*
                     }
                 }
+                pub fn vote_weight(self, game: &Game, actor_ref: PlayerReference, voter: PlayerReference, vote_type: VoteType, weight: u8) -> u8 {
+                    match self {
+                        $(Self::$name(role_struct) => role_struct.vote_weight(game, actor_ref, voter, vote_type, weight)),*
+                    }
+                }
+                pub fn can_whisper(self, game: &Game, actor_ref: PlayerReference, whisperer: PlayerReference, whisperee: PlayerReference, allowed: bool) -> bool {
+                    match self {
+                        $(Self::$name(role_struct) => role_struct.can_whisper(game, actor_ref, whisperer, whisperee, allowed)),*
+                    }
+                }
+                pub fn reads_whisper(self, game: &Game, actor_ref: PlayerReference, whisperer: PlayerReference, whisperee: PlayerReference) -> bool {
+                    match self {
+                        $(Self::$name(role_struct) => role_struct.reads_whisper(game, actor_ref, whisperer, whisperee)),*
+                    }
+                }
                 pub fn get_client_role_state(self, game: &Game, actor_ref: PlayerReference) -> ClientRoleStateEnum {
                     match self {
                         $(Self::$name(role_struct) => ClientRoleStateEnum::$name(role_struct.get_client_role_state(game, actor_ref))),*
@@ -128,11 +128,10 @@ impl RoleStateImpl for Recruiter {
         if let Some(random_mafia_player) = random_mafia_player {
 
             let random_town_role = RoleOutline {options: vec1![RoleOutlineOption {
-                win_condition: Default::default(), 
-                insider_groups: Default::default(), 
-                roles: RoleOutlineOptionRoles::RoleSet{ role_set: RoleSet::TownCommon } 
+                roles: RoleOutlineOptionRoles::RoleSet{ role_set: RoleSet::TownCommon },
+                ..Default::default()
             }]}.get_random_role_assignments(
-                &game.settings.enabled_roles,
+                &game.settings,
                 PlayerReference::all_players(game).map(|p|p.role(game)).collect::<Vec<_>>().as_slice()
             ).map(|assignment| assignment.role);
 
@@ -1,6 +1,7 @@
 use serde::Serialize;
 
 use crate::game::components::detained::Detained;
+use crate::game::components::night_resolution_trace::{NightResolutionTrace, NightTraceEvent};
 use crate::game::{attack_power::DefensePower, chat::ChatMessageVariant};
 use crate::game::player::PlayerReference;
 
@@ -29,6 +30,11 @@ impl RoleStateImpl for Transporter {
         
         first_visit.target.push_night_message(game, ChatMessageVariant::Transported);
         second_visit.target.push_night_message(game, ChatMessageVariant::Transported);
+        NightResolutionTrace::record(game, NightTraceEvent::Transported {
+            transporter: actor_ref,
+            first: first_visit.target,
+            second: second_visit.target
+        });
     
         for player_ref in PlayerReference::all_players(game){
             if player_ref == actor_ref {continue;}
@@ -56,7 +56,7 @@ impl TrueWildcard {
         if 
             role_can_generate(
                 role, 
-                &game.settings.enabled_roles, 
+                &game.settings, 
                 &Vec::new(),    //True wildcard can be whatever they want
             )
         {
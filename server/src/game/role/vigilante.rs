@@ -66,7 +66,9 @@ impl RoleStateImpl for Vigilante {
                     }       
 
                     VigilanteState::NotLoaded => {
-                        self.state = VigilanteState::Loaded { bullets: game.num_players().div_ceil(5) };
+                        self.state = VigilanteState::Loaded {
+                            bullets: game.settings.role_uses(Role::Vigilante).unwrap_or(game.num_players().div_ceil(5))
+                        };
                     }
 
                     _ => {},
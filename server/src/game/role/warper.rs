@@ -1,6 +1,7 @@
 use serde::Serialize;
 
 use crate::game::components::detained::Detained;
+use crate::game::components::night_resolution_trace::{NightResolutionTrace, NightTraceEvent};
 use crate::game::grave::Grave;
 use crate::game::phase::PhaseType;
 use crate::game::win_condition::WinCondition;
@@ -29,6 +30,11 @@ impl RoleStateImpl for Warper {
         
         
         first_visit.target.push_night_message(game, ChatMessageVariant::Transported);
+        NightResolutionTrace::record(game, NightTraceEvent::Warped {
+            warper: actor_ref,
+            target: first_visit.target,
+            destination: second_visit.target
+        });
         actor_ref.push_night_message(game, ChatMessageVariant::TargetHasRole { role: first_visit.target.role(game) });
     
         for player_ref in PlayerReference::all_players(game){
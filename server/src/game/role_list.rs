@@ -7,32 +7,54 @@ use vec1::{
 
 use crate::vec_set::VecSet;
 
-use super::{components::insider_group::InsiderGroupID, game_conclusion::GameConclusion, role::Role};
+use super::{components::insider_group::InsiderGroupID, game_conclusion::GameConclusion, role::Role, role_list_generation::{RoleListGenerationError, RoleListGenerator}, settings::Settings};
 
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct RoleList(pub Vec<RoleOutline>);
 impl RoleList {
     /// Output is the same order as the rolelist
-    pub fn create_random_role_assignments(&self, enabled_roles: &VecSet<Role>) -> Option<Vec<RoleAssignment>> {
-        let mut generated_data = Vec::<RoleAssignment>::new();
-        for entry in self.0.iter(){
-            if let Some(player_initialization_data) = entry.get_random_role_assignments(
-                enabled_roles, &generated_data.iter().map(|datum| datum.role).collect::<Vec<Role>>()
-            ){
-                generated_data.push(player_initialization_data);
-            }else{
-                return None;
-            }
-        }
-        Some(generated_data)
+    pub fn create_random_role_assignments(&self, settings: &Settings) -> Result<Vec<RoleAssignment>, RoleListGenerationError> {
+        RoleListGenerator::new(self, settings).generate()
+    }
+    pub fn from_json(json: &serde_json::Value) -> Result<Self, RoleListParseError> {
+        let serde_json::Value::Array(outlines) = json else {
+            return Err(RoleListParseError::new(None, "expected a list of outlines"));
+        };
+
+        outlines.iter()
+            .enumerate()
+            .map(|(index, outline)| RoleOutline::from_json(outline)
+                .map_err(|error| RoleListParseError { outline: Some(index as u8), ..error })
+            )
+            .collect::<Result<Vec<_>, _>>()
+            .map(RoleList)
     }
-    pub fn simplify(&mut self){
+    pub fn simplify(&mut self, custom_role_sets: &[CustomRoleSet]){
         for entry in self.0.iter_mut(){
-            entry.simplify();
+            entry.simplify(custom_role_sets);
         }
     }
-    pub fn sort(&mut self){
-        self.0.sort_by_key(|r| r.get_role_assignments().len());
+    pub fn sort(&mut self, custom_role_sets: &[CustomRoleSet]){
+        self.0.sort_by_key(|r| r.get_role_assignments(custom_role_sets).len());
+    }
+}
+
+/// A rule across the whole role list that generation must follow
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum RoleListConstraint {
+    AtLeastOne{roles: RoleOutlineOptionRoles},
+    AtMost{roles: RoleOutlineOptionRoles, count: u8},
+}
+impl RoleListConstraint {
+    /// Generation tries again for every constraint when it fails, so there can't be too many
+    pub const MAX_COUNT: usize = 20;
+
+    pub fn roles(&self) -> &RoleOutlineOptionRoles {
+        match self {
+            Self::AtLeastOne { roles } |
+            Self::AtMost { roles, .. } => roles
+        }
     }
 }
 
@@ -69,46 +91,72 @@ impl<'de> Deserialize<'de> for RoleOutline {
 impl Default for RoleOutline {
     fn default() -> Self {
         Self {options: vec1![RoleOutlineOption{
-            win_condition: Default::default(),
-            insider_groups: Default::default(),
-            roles: RoleOutlineOptionRoles::RoleSet { role_set: RoleSet::Any }
+            roles: RoleOutlineOptionRoles::RoleSet { role_set: RoleSet::Any },
+            ..Default::default()
         }]}
     }
 }
 impl RoleOutline{
+    pub fn from_json(json: &serde_json::Value) -> Result<Self, RoleListParseError> {
+        let serde_json::Value::Array(options) = json else {
+            return Err(RoleListParseError::new(None, "expected a list of options"));
+        };
+
+        let options = options.iter()
+            .enumerate()
+            .map(|(index, option)| RoleOutlineOption::from_json(option)
+                .map_err(|error| RoleListParseError { option: Some(index as u8), ..error })
+            )
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Vec1::try_from_vec(options)
+            .map(|options| Self { options })
+            .map_err(|_| RoleListParseError::new(None, "an outline needs at least one option"))
+    }
     pub fn new_exact(role: Role)->RoleOutline{
         RoleOutline{options: vec1![RoleOutlineOption{
-            win_condition: Default::default(),
-            insider_groups: Default::default(),
-            roles: RoleOutlineOptionRoles::Role{role}
+            roles: RoleOutlineOptionRoles::Role{role},
+            ..Default::default()
         }]}
     }
-    pub fn get_role_assignments(&self) -> Vec<RoleAssignment> {
+    pub fn get_role_assignments(&self, custom_role_sets: &[CustomRoleSet]) -> Vec<RoleAssignment> {
+        self.get_weighted_role_assignments(custom_role_sets).into_iter()
+            .map(|(assignment, _)| assignment)
+            .collect()
+    }
+    /// Each role gets the weight of the option it came from
+    pub fn get_weighted_role_assignments(&self, custom_role_sets: &[CustomRoleSet]) -> Vec<(RoleAssignment, u8)> {
         self.options.iter()
             .flat_map(|r| 
-                r.roles.get_roles().into_iter()
-                    .map(|role| RoleAssignment{
-                        role,
-                        insider_groups: r.insider_groups.clone(),
-                        win_condition: r.win_condition.clone()
-                    })
+                r.roles.get_roles(custom_role_sets).into_iter()
+                    .map(|role| (
+                        RoleAssignment{
+                            role,
+                            insider_groups: r.insider_groups.clone(),
+                            win_condition: r.win_condition.clone()
+                        },
+                        r.weight
+                    ))
             ).collect()
     }
-    pub fn get_random_role_assignments(&self, enabled_roles: &VecSet<Role>, taken_roles: &[Role]) -> Option<RoleAssignment> {
-        let options = self.get_role_assignments()
+    pub fn get_random_role_assignments(&self, settings: &Settings, taken_roles: &[Role]) -> Option<RoleAssignment> {
+        let options = self.get_weighted_role_assignments(&settings.custom_role_sets)
             .into_iter()
-            .filter(|r|role_can_generate(r.role, enabled_roles, taken_roles))
+            .filter(|(r, _)|role_can_generate(r.role, settings, taken_roles))
             .collect::<Vec<_>>();
-        options.choose(&mut rand::rng()).cloned()
+        options.choose_weighted(&mut rand::rng(), |(_, weight)| *weight)
+            .ok()
+            .map(|(assignment, _)| assignment.clone())
     }
-    pub fn simplify(&mut self){
+    pub fn simplify(&mut self, custom_role_sets: &[CustomRoleSet]){
         let mut new_options = self.options.to_vec();
 
         new_options = new_options.into_iter().collect::<VecSet<_>>().into_iter().collect();
 
         for option_a in self.options.iter(){
             for option_b in self.options.iter(){
-                if option_a.roles.is_subset(&option_b.roles) && option_a != option_b {
+                // Sets with the same roles are subsets of each other, so only one of them gets removed
+                if option_a.roles.is_subset(&option_b.roles, custom_role_sets) && option_a != option_b && new_options.contains(option_b) {
                     new_options.retain(|r| r != option_a);
                 }
             }
@@ -117,7 +165,7 @@ impl RoleOutline{
         let mut new_options = Vec1::try_from_vec(new_options)
             .expect("It is impossible to have two sets that are not equal but are subsets of each other, role_list.rs: RoleOutline::simplify");
 
-        new_options.sort();
+        new_options.sort_by_key(|option| std::cmp::Reverse(option.roles.get_roles(custom_role_sets).len()));
 
         *self = RoleOutline{options: new_options};
     }
@@ -152,7 +200,7 @@ impl RoleOutlineOptionInsiderGroups {
     }
 }
 
-#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, PartialOrd, Ord)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, PartialOrd, Ord)]
 #[serde(rename_all = "camelCase")]
 pub struct RoleOutlineOption {
     #[serde(flatten)]
@@ -161,49 +209,118 @@ pub struct RoleOutlineOption {
     pub win_condition: RoleOutlineOptionWinCondition,
     #[serde(flatten, skip_serializing_if = "RoleOutlineOptionInsiderGroups::is_default")]
     pub insider_groups: RoleOutlineOptionInsiderGroups,
+    /// How likely each of this option's roles is to be picked compared to the outline's other options.
+    /// An option with weight 0 never generates.
+    #[serde(skip_serializing_if = "RoleOutlineOption::is_default_weight")]
+    pub weight: u8,
+}
+impl Default for RoleOutlineOption {
+    fn default() -> Self {
+        Self {
+            roles: Default::default(),
+            win_condition: Default::default(),
+            insider_groups: Default::default(),
+            weight: Self::DEFAULT_WEIGHT
+        }
+    }
 }
-
-/// Watch this!
 impl<'de> Deserialize<'de> for RoleOutlineOption {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: serde::Deserializer<'de> {
+        Self::from_json(&serde_json::Value::deserialize(deserializer)?)
+            .map_err(serde::de::Error::custom)
+    }
+}
+impl RoleOutlineOption {
+    pub const DEFAULT_WEIGHT: u8 = 1;
+    fn is_default_weight(weight: &u8) -> bool {
+        *weight == Self::DEFAULT_WEIGHT
+    }
 
-        let mut option = RoleOutlineOption::default();
-        
-        let json = serde_json::Value::deserialize(deserializer)?;
+    const FIELDS: [&'static str; 6] = ["role", "roleSet", "customRoleSet", "winIfAny", "insiderGroups", "weight"];
 
-        if let serde_json::Value::Object(map) = json {
-            if let Some(value) = map.get("winIfAny") {
-                if let Ok(string_win_condition) = serde_json::to_string(value) {
-                    if let Ok(win_if_any) = serde_json::from_str(string_win_condition.as_str()) {
-                        option.win_condition = RoleOutlineOptionWinCondition::GameConclusionReached { win_if_any}
-                    }
-                }
-            }
-            if let Some(value) = map.get("insiderGroups") {
-                if let Ok(string_insider_groups) = serde_json::to_string(value) {
-                    if let Ok(insider_groups) = serde_json::from_str(string_insider_groups.as_str()) {
-                        option.insider_groups = RoleOutlineOptionInsiderGroups::Custom { insider_groups }
-                    }
-                }
-            }
-            if let Some(value) = map.get("roleSet") {
-                if let Ok(string_role_set) = serde_json::to_string(value) {
-                    if let Ok(role_set) = serde_json::from_str(string_role_set.as_str()) {
-                        option.roles = RoleOutlineOptionRoles::RoleSet { role_set }
-                    }
-                }
-            } else if let Some(value) = map.get("role") {
-                if let Ok(string_role) = serde_json::to_string(value) {
-                    if let Ok(role) = serde_json::from_str(string_role.as_str()) {
-                        option.roles = RoleOutlineOptionRoles::Role { role }
-                    }
-                }
-            }
+    /// Fails on anything it doesn't understand, so a typo can't quietly turn the option into something else
+    pub fn from_json(json: &serde_json::Value) -> Result<Self, RoleListParseError> {
+        let serde_json::Value::Object(map) = json else {
+            return Err(RoleListParseError::new(None, "expected an object"));
+        };
+
+        if let Some(field) = map.keys().find(|field| !Self::FIELDS.contains(&field.as_str())) {
+            return Err(RoleListParseError::new(Some(field), "unknown field"));
         }
 
-        Ok(option)
+        let roles = match (map.get("role"), map.get("roleSet"), map.get("customRoleSet")) {
+            (Some(role), None, None) => RoleOutlineOptionRoles::Role {
+                role: Self::parse_field("role", role)?
+            },
+            (None, Some(role_set), None) => RoleOutlineOptionRoles::RoleSet {
+                role_set: Self::parse_field("roleSet", role_set)?
+            },
+            (None, None, Some(custom_role_set)) => RoleOutlineOptionRoles::CustomRoleSet {
+                custom_role_set: Self::parse_field("customRoleSet", custom_role_set)?
+            },
+            (None, None, None) => return Err(RoleListParseError::new(None, "missing role, roleSet or customRoleSet")),
+            _ => return Err(RoleListParseError::new(None, "expected only one of role, roleSet and customRoleSet")),
+        };
+
+        let win_condition = match map.get("winIfAny") {
+            Some(win_if_any) => RoleOutlineOptionWinCondition::GameConclusionReached {
+                win_if_any: Self::parse_field("winIfAny", win_if_any)?
+            },
+            None => RoleOutlineOptionWinCondition::RoleDefault,
+        };
+
+        let insider_groups = match map.get("insiderGroups") {
+            Some(insider_groups) => RoleOutlineOptionInsiderGroups::Custom {
+                insider_groups: Self::parse_field("insiderGroups", insider_groups)?
+            },
+            None => RoleOutlineOptionInsiderGroups::RoleDefault,
+        };
+
+        let weight = match map.get("weight") {
+            Some(weight) => Self::parse_field("weight", weight)?,
+            None => Self::DEFAULT_WEIGHT,
+        };
+
+        Ok(Self { roles, win_condition, insider_groups, weight })
+    }
+
+    fn parse_field<T: serde::de::DeserializeOwned>(field: &str, value: &serde_json::Value) -> Result<T, RoleListParseError> {
+        serde_json::from_value(value.clone())
+            .map_err(|error| RoleListParseError::new(Some(field), &error.to_string()))
+    }
+}
+
+/// Where and why a role list from a client couldn't be read
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RoleListParseError {
+    /// None if the role list itself is malformed
+    pub outline: Option<u8>,
+    /// None if the outline itself is malformed
+    pub option: Option<u8>,
+    /// None if the option itself is malformed
+    pub field: Option<String>,
+    pub message: String,
+}
+impl RoleListParseError {
+    fn new(field: Option<&str>, message: &str) -> Self {
+        Self { outline: None, option: None, field: field.map(str::to_string), message: message.to_string() }
+    }
+}
+impl std::fmt::Display for RoleListParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(outline) = self.outline {
+            write!(f, "outline {outline}, ")?;
+        }
+        if let Some(option) = self.option {
+            write!(f, "option {option}, ")?;
+        }
+        if let Some(field) = &self.field {
+            write!(f, "{field}: ")?;
+        }
+        write!(f, "{}", self.message)
     }
 }
 
@@ -215,6 +332,44 @@ pub enum RoleOutlineOptionRoles {
     RoleSet{role_set: RoleSet},
     #[serde(rename_all = "camelCase")]
     Role{role: Role},
+    /// One of the host's custom role sets, by name. Its roles are looked up in the settings when they're needed,
+    /// so it always matches the current set, and has no roles if the set was removed.
+    #[serde(rename_all = "camelCase")]
+    CustomRoleSet{custom_role_set: String},
+}
+
+/// A named role set made by the host, like "Town Power" = Jailor + Mayor + Veteran
+#[derive(Debug, Clone, Hash, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomRoleSet {
+    pub name: String,
+    pub roles: Vec<Role>,
+}
+impl CustomRoleSet {
+    pub const MAX_NAME_LENGTH: usize = 30;
+    pub const MAX_COUNT: usize = 20;
+}
+
+/// Why the host's role list constraints weren't accepted
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum RoleListConstraintError {
+    TooManyConstraints,
+    UnknownCustomRoleSet{constraint: u8},
+    NoRoles{constraint: u8},
+    CountLargerThanRoleList{constraint: u8},
+}
+
+/// Why the host's custom role sets weren't accepted
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum CustomRoleSetError {
+    TooManySets,
+    InvalidName{set: u8},
+    DuplicateName{set: u8},
+    EmptySet{set: u8},
+    DuplicateRole{set: u8, role: Role},
+    RoleNotEnabled{set: u8, role: Role},
 }
 impl Default for RoleOutlineOptionRoles {
     fn default() -> Self {
@@ -222,17 +377,23 @@ impl Default for RoleOutlineOptionRoles {
     }
 }
 impl RoleOutlineOptionRoles{
-    pub fn get_roles(&self) -> Vec<Role> {
+    pub fn get_roles(&self, custom_role_sets: &[CustomRoleSet]) -> Vec<Role> {
         match self {
             RoleOutlineOptionRoles::RoleSet { role_set } => {
                 role_set.get_roles()
             }
             RoleOutlineOptionRoles::Role { role } => 
-                vec![*role]
+                vec![*role],
+            RoleOutlineOptionRoles::CustomRoleSet { custom_role_set } =>
+                custom_role_sets.iter()
+                    .find(|set| set.name == *custom_role_set)
+                    .map(|set| set.roles.clone())
+                    .unwrap_or_default()
         }
     }
-    pub fn is_subset(&self, other: &RoleOutlineOptionRoles) -> bool {
-        self.get_roles().iter().all(|r|other.get_roles().contains(r))
+    pub fn is_subset(&self, other: &RoleOutlineOptionRoles, custom_role_sets: &[CustomRoleSet]) -> bool {
+        let other_roles = other.get_roles(custom_role_sets);
+        self.get_roles(custom_role_sets).iter().all(|r|other_roles.contains(r))
     }
 }
 impl PartialOrd for RoleOutlineOptionRoles {
@@ -240,9 +401,15 @@ impl PartialOrd for RoleOutlineOptionRoles {
         Some(self.cmp(other))
     }
 }
+/// Bigger sets first. Custom role sets can't be sized without the settings, so they're compared by name after the rest
 impl Ord for RoleOutlineOptionRoles {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        other.get_roles().len().cmp(&self.get_roles().len())
+        match (self, other) {
+            (Self::CustomRoleSet { custom_role_set: a }, Self::CustomRoleSet { custom_role_set: b }) => a.cmp(b),
+            (Self::CustomRoleSet { .. }, _) => std::cmp::Ordering::Greater,
+            (_, Self::CustomRoleSet { .. }) => std::cmp::Ordering::Less,
+            _ => other.get_roles(&[]).len().cmp(&self.get_roles(&[]).len())
+        }
     }
 }
 
@@ -270,6 +437,15 @@ pub enum RoleSet {
     Minions
 }
 impl RoleSet{
+    pub fn values() -> Vec<RoleSet> {
+        vec![
+            RoleSet::Any,
+            RoleSet::Town, RoleSet::TownCommon, RoleSet::TownInvestigative, RoleSet::TownProtective, RoleSet::TownKilling, RoleSet::TownSupport,
+            RoleSet::Mafia, RoleSet::MafiaSupport, RoleSet::MafiaKilling,
+            RoleSet::Cult, RoleSet::Fiends,
+            RoleSet::Neutral, RoleSet::Minions
+        ]
+    }
     pub fn get_roles(&self) -> Vec<Role> {
         match self {
             RoleSet::Any => Role::values(),
@@ -359,12 +535,12 @@ impl RoleSet{
 
 
 
-pub fn role_can_generate(role: Role, enabled_roles: &VecSet<Role>, taken_roles: &[Role]) -> bool {
-    if !enabled_roles.contains(&role) {
+pub fn role_can_generate(role: Role, settings: &Settings, taken_roles: &[Role]) -> bool {
+    if !settings.enabled_roles.contains(&role) {
         return false;
     }
 
-    match role.maximum_count() {
+    match settings.role_maximum_count(role) {
         Some(max) => taken_roles.iter().filter(|r|**r==role).count() < max.into(),
         None => true,
     }
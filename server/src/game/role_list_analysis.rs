@@ -0,0 +1,134 @@
+use serde::Serialize;
+
+use crate::{vec_map::VecMap, vec_set::VecSet};
+
+use super::{
+    game_conclusion::GameConclusion,
+    role::Role,
+    role_list::{RoleAssignment, RoleOutlineOptionInsiderGroups, RoleOutlineOptionWinCondition, RoleSet},
+    role_list_generation::{RoleListGenerationError, RoleListGenerator},
+    settings::Settings,
+    win_condition::WinCondition
+};
+
+/// What a role list is likely to generate, found by generating it many times.
+/// Lets the host see problems with their settings before starting the game.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RoleListAnalysis {
+    /// Set if the role list can't be generated at all
+    pub error: Option<RoleListGenerationError>,
+    /// Outlines that can never generate because none of their roles are enabled
+    pub outlines_with_no_roles: Vec<u8>,
+    /// Chance that at least one player gets each role
+    pub role_chances: VecMap<Role, f32>,
+    /// Chance that at least one player gets a role from each role set
+    pub role_set_chances: VecMap<RoleSet, f32>,
+    /// Chance that at least one player wins with each game conclusion
+    pub conclusion_chances: VecMap<GameConclusion, f32>,
+    /// Whether a generated role list can end the game before it starts.
+    /// Starting the game rerolls these, so the game only fails to start if it happens every time.
+    pub can_end_instantly: bool,
+}
+
+impl RoleListAnalysis {
+    const SAMPLES: u16 = 200;
+
+    pub fn new(settings: &Settings) -> Self {
+        let generator = RoleListGenerator::new(&settings.role_list, settings);
+        let outlines_with_no_roles = generator.outlines_with_no_roles();
+
+        let mut role_counts: VecMap<Role, u16> = VecMap::new();
+        let mut role_set_counts: VecMap<RoleSet, u16> = VecMap::new();
+        let mut conclusion_counts: VecMap<GameConclusion, u16> = VecMap::new();
+        let mut can_end_instantly = false;
+
+        for _ in 0..Self::SAMPLES {
+            let assignments = match settings.role_list.create_random_role_assignments(settings) {
+                Ok(assignments) => assignments,
+                Err(error) => return Self {
+                    error: Some(error),
+                    outlines_with_no_roles,
+                    role_chances: VecMap::new(),
+                    role_set_chances: VecMap::new(),
+                    conclusion_chances: VecMap::new(),
+                    can_end_instantly: false,
+                }
+            };
+
+            let roles: VecSet<Role> = assignments.iter().map(|assignment| assignment.role).collect();
+            for role in roles.iter() {
+                Self::increment(&mut role_counts, *role);
+            }
+            for role_set in RoleSet::values() {
+                if role_set.get_roles().iter().any(|role| roles.contains(role)) {
+                    Self::increment(&mut role_set_counts, role_set);
+                }
+            }
+
+            let players: Vec<(Role, WinCondition, bool)> = assignments.iter()
+                .map(|assignment| {
+                    let win_condition = Self::win_condition(assignment);
+                    let keeps_game_running = Self::keeps_game_running(settings, assignment, &win_condition);
+                    (assignment.role, win_condition, keeps_game_running)
+                })
+                .collect();
+
+            let conclusions: VecSet<GameConclusion> = players.iter()
+                .filter_map(|(_, win_condition, _)| win_condition.required_resolution_states_for_win())
+                .flatten()
+                .collect();
+            for conclusion in conclusions {
+                Self::increment(&mut conclusion_counts, conclusion);
+            }
+
+            let living_players: Vec<(Role, &WinCondition, bool)> = players.iter()
+                .map(|(role, win_condition, keeps_game_running)| (*role, win_condition, *keeps_game_running))
+                .collect();
+            if GameConclusion::game_is_over_for_living_players(&living_players).is_some() {
+                can_end_instantly = true;
+            }
+        }
+
+        Self {
+            error: None,
+            outlines_with_no_roles,
+            role_chances: Self::chances(role_counts),
+            role_set_chances: Self::chances(role_set_counts),
+            conclusion_chances: Self::chances(conclusion_counts),
+            can_end_instantly,
+        }
+    }
+
+    /// Matches how Game::new sets win conditions before checking if the game ends instantly
+    fn win_condition(assignment: &RoleAssignment) -> WinCondition {
+        match &assignment.win_condition {
+            RoleOutlineOptionWinCondition::RoleDefault => assignment.role.default_state().default_win_condition(),
+            RoleOutlineOptionWinCondition::GameConclusionReached { win_if_any } => WinCondition::GameConclusionReached {
+                win_if_any: win_if_any.iter().cloned().collect()
+            },
+        }
+    }
+
+    fn keeps_game_running(settings: &Settings, assignment: &RoleAssignment, win_condition: &WinCondition) -> bool {
+        let insider_groups = match &assignment.insider_groups {
+            RoleOutlineOptionInsiderGroups::RoleDefault => assignment.role.default_state().default_revealed_groups(),
+            RoleOutlineOptionInsiderGroups::Custom { insider_groups } => insider_groups.clone(),
+        };
+        GameConclusion::player_keeps_game_running(settings, assignment.role, win_condition, |group| insider_groups.contains(&group))
+    }
+
+    fn increment<K: Eq>(counts: &mut VecMap<K, u16>, key: K) {
+        if let Some(count) = counts.get_mut(&key) {
+            *count = count.saturating_add(1);
+        } else {
+            counts.insert(key, 1);
+        }
+    }
+
+    fn chances<K: Eq>(counts: VecMap<K, u16>) -> VecMap<K, f32> {
+        counts.into_iter()
+            .map(|(key, count)| (key, count as f32 / Self::SAMPLES as f32))
+            .collect()
+    }
+}
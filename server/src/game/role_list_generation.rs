@@ -0,0 +1,202 @@
+use std::collections::HashSet;
+
+use rand::Rng;
+use serde::Serialize;
+
+use super::{
+    role::Role,
+    role_list::{role_can_generate, RoleAssignment, RoleList, RoleListConstraint},
+    settings::Settings
+};
+
+/// Why a role list can't be generated
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum RoleListGenerationError {
+    /// None of the outline's roles are enabled
+    OutlineHasNoRoles{outline: u8},
+    /// The outlines can't be filled without going over a role's maximum count
+    MaximumCountExceeded,
+    /// The list can be generated without this constraint, but not with it
+    ConstraintCannotBeMet{constraint: u8},
+    /// Gave up before finding out whether the list can be generated
+    TooComplex,
+}
+
+/// Generates a role list by searching for an assignment that follows every constraint.
+/// Candidates are tried in a weighted random order, so the result is still random.
+/// Roles that are interchangeable as far as the constraints are concerned are only tried once per outline,
+/// which keeps the search small enough to prove when a list is impossible.
+pub struct RoleListGenerator<'a> {
+    settings: &'a Settings,
+    /// The roles each outline can generate, in role list order
+    candidates: Vec<Vec<(RoleAssignment, u8)>>,
+    steps: u32,
+}
+
+/// The roles each constraint is about
+struct ConstraintRoles<'a> {
+    constraint: &'a RoleListConstraint,
+    roles: HashSet<Role>,
+}
+
+impl<'a> RoleListGenerator<'a> {
+    const MAX_STEPS: u32 = 20_000;
+
+    pub fn new(role_list: &RoleList, settings: &'a Settings) -> Self {
+        Self {
+            settings,
+            candidates: role_list.0.iter()
+                .map(|outline| outline.get_weighted_role_assignments(&settings.custom_role_sets).into_iter()
+                    .filter(|(assignment, weight)| *weight > 0 && settings.enabled_roles.contains(&assignment.role))
+                    .collect()
+                ).collect(),
+            steps: 0
+        }
+    }
+
+    /// Output is the same order as the rolelist
+    pub fn generate(mut self) -> Result<Vec<RoleAssignment>, RoleListGenerationError> {
+        if let Some(&outline) = self.outlines_with_no_roles().first() {
+            return Err(RoleListGenerationError::OutlineHasNoRoles { outline });
+        }
+
+        let settings = self.settings;
+        let constraints: Vec<ConstraintRoles> = settings.role_list_constraints.iter()
+            .map(|constraint| ConstraintRoles {
+                constraint,
+                roles: constraint.roles().get_roles(&settings.custom_role_sets).into_iter().collect()
+            })
+            .collect();
+
+        if let Some(assignments) = self.solve(&constraints)? {
+            return Ok(assignments);
+        }
+
+        // Add constraints one at a time to find the one that makes the list impossible
+        for count in 0..constraints.len() {
+            if self.solve(&constraints[..count])?.is_none() {
+                return Err(Self::error_for_constraint_count(count));
+            }
+        }
+        Err(Self::error_for_constraint_count(constraints.len()))
+    }
+
+    /// Outlines that can never generate because none of their roles are enabled
+    pub fn outlines_with_no_roles(&self) -> Vec<u8> {
+        self.candidates.iter()
+            .enumerate()
+            .filter(|(_, candidates)| candidates.is_empty())
+            .map(|(outline, _)| outline as u8)
+            .collect()
+    }
+
+    fn error_for_constraint_count(count: usize) -> RoleListGenerationError {
+        match count.checked_sub(1) {
+            Some(constraint) => RoleListGenerationError::ConstraintCannotBeMet { constraint: u8::try_from(constraint).unwrap_or(u8::MAX) },
+            None => RoleListGenerationError::MaximumCountExceeded,
+        }
+    }
+
+    fn solve(&mut self, constraints: &[ConstraintRoles]) -> Result<Option<Vec<RoleAssignment>>, RoleListGenerationError> {
+        self.steps = 0;
+
+        // Fill the most restricted outlines first
+        let mut order: Vec<usize> = (0..self.candidates.len()).collect();
+        order.sort_by_key(|outline| self.candidates[*outline].len());
+
+        let mut chosen = vec![None; self.candidates.len()];
+        let mut taken_roles = Vec::new();
+
+        if self.search(constraints, &order, &mut chosen, &mut taken_roles)? {
+            Ok(Some(chosen.into_iter().flatten().collect()))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn search(
+        &mut self,
+        constraints: &[ConstraintRoles],
+        order: &[usize],
+        chosen: &mut Vec<Option<RoleAssignment>>,
+        taken_roles: &mut Vec<Role>
+    ) -> Result<bool, RoleListGenerationError> {
+        let Some((&outline, remaining_order)) = order.split_first() else {
+            return Ok(true);
+        };
+
+        self.steps += 1;
+        if self.steps > Self::MAX_STEPS {
+            return Err(RoleListGenerationError::TooComplex);
+        }
+
+        let mut tried_signatures = Vec::new();
+        for assignment in self.weighted_shuffle(outline) {
+            if !role_can_generate(assignment.role, self.settings, taken_roles) {continue}
+
+            let signature = self.signature(assignment.role, constraints);
+            if tried_signatures.contains(&signature) {continue}
+            tried_signatures.push(signature);
+
+            taken_roles.push(assignment.role);
+            if
+                self.can_still_be_met(constraints, remaining_order, taken_roles) &&
+                self.search(constraints, remaining_order, chosen, taken_roles)?
+            {
+                chosen[outline] = Some(assignment);
+                return Ok(true);
+            }
+            taken_roles.pop();
+        }
+
+        Ok(false)
+    }
+
+    /// Two roles with the same signature can be swapped without changing which constraints are met
+    fn signature(&self, role: Role, constraints: &[ConstraintRoles]) -> (Option<Role>, Vec<bool>) {
+        (
+            self.settings.role_maximum_count(role).map(|_| role),
+            constraints.iter().map(|constraint| constraint.roles.contains(&role)).collect()
+        )
+    }
+
+    fn can_still_be_met(&self, constraints: &[ConstraintRoles], remaining_order: &[usize], taken_roles: &[Role]) -> bool {
+        constraints.iter().all(|constraint| {
+            let taken_count = taken_roles.iter().filter(|role| constraint.roles.contains(role)).count();
+            match constraint.constraint {
+                RoleListConstraint::AtMost { count, .. } => {
+                    // Outlines that can only generate roles from the set will add to the count no matter what
+                    let forced_count = remaining_order.iter()
+                        .filter(|outline|
+                            self.candidates[**outline].iter().all(|(assignment, _)| constraint.roles.contains(&assignment.role))
+                        )
+                        .count();
+                    taken_count + forced_count <= *count as usize
+                },
+                RoleListConstraint::AtLeastOne { .. } => {
+                    taken_count > 0 ||
+                    remaining_order.iter().any(|outline|
+                        self.candidates[*outline].iter().any(|(assignment, _)|
+                            constraint.roles.contains(&assignment.role) &&
+                            role_can_generate(assignment.role, self.settings, taken_roles)
+                        )
+                    )
+                }
+            }
+        })
+    }
+
+    /// Heavier candidates are more likely to come first
+    fn weighted_shuffle(&self, outline: usize) -> Vec<RoleAssignment> {
+        let mut rng = rand::rng();
+        let mut keyed: Vec<(f64, RoleAssignment)> = self.candidates[outline].iter()
+            .map(|(assignment, weight)| (
+                rng.random::<f64>().powf(1.0 / *weight as f64),
+                assignment.clone()
+            ))
+            .collect();
+        keyed.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+        keyed.into_iter().map(|(_, assignment)| assignment).collect()
+    }
+}
@@ -2,18 +2,319 @@ use std::time::Duration;
 
 use serde::{Serialize, Deserialize};
 
-use crate::vec_set::VecSet;
+use crate::{vec_map::VecMap, vec_set::VecSet};
 
-use super::{modifiers::ModifierType, phase::PhaseType, role::Role, role_list::RoleList};
+use super::{chat::ChatFilter, components::{insider_group::{CustomInsiderGroup, CustomInsiderGroupError, InsiderGroupID}, nomination_ties::NominationTieBreak}, game_conclusion::{CustomFaction, CustomFactionError, GameConclusion}, modifiers::{ModifierState, ModifierType}, phase::PhaseType, role::Role, role_list::{CustomRoleSet, CustomRoleSetError, RoleList, RoleListConstraint, RoleListConstraintError, RoleOutlineOption, RoleOutlineOptionInsiderGroups, RoleOutlineOptionRoles, RoleOutlineOptionWinCondition}};
 
+/// Missing fields use their default, so settings saved before a field was added still load
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct Settings{
     pub role_list: RoleList,
+    pub role_list_constraints: Vec<RoleListConstraint>,
     pub phase_times: PhaseTimeSettings,
     pub enabled_roles: VecSet<Role>,
-    pub enabled_modifiers: VecSet<ModifierType>,
+    #[serde(with = "enabled_modifiers_as_list")]
+    pub enabled_modifiers: VecMap<ModifierType, ModifierState>,
+    pub role_configurations: VecMap<Role, RoleConfiguration>,
+    pub custom_role_sets: Vec<CustomRoleSet>,
+    pub custom_insider_groups: Vec<CustomInsiderGroup>,
+    pub custom_factions: Vec<CustomFaction>,
+    pub spectator_settings: SpectatorSettings,
+    pub whisper_settings: WhisperSettings,
+    pub chat_filter: ChatFilter,
+    pub nomination_tie_break: NominationTieBreak,
+}
+impl Settings{
+    /// Checks the same things the lobby checks when the host changes each of these settings
+    pub fn has_valid_parameters(&self)->bool{
+        self.enabled_modifiers.values().all(ModifierState::has_valid_parameters) &&
+        self.role_configurations.iter().all(|(role, configuration)| configuration.is_valid_for(*role)) &&
+        self.check_custom_role_sets(&self.custom_role_sets).is_ok() &&
+        self.check_role_list_constraints(&self.role_list_constraints).is_ok() &&
+        Self::check_custom_insider_groups(&self.custom_insider_groups).is_ok() &&
+        Self::check_custom_factions(&self.custom_factions).is_ok() &&
+        self.chat_filter.check().is_ok()
+    }
+    /// Custom role sets can only use enabled roles
+    pub fn check_custom_role_sets(&self, custom_role_sets: &[CustomRoleSet])->Result<(), CustomRoleSetError>{
+        if custom_role_sets.len() > CustomRoleSet::MAX_COUNT {
+            return Err(CustomRoleSetError::TooManySets);
+        }
+        let names: Vec<&str> = custom_role_sets.iter().map(|set| set.name.as_str()).collect();
+        check_custom_names(&names, CustomRoleSet::MAX_NAME_LENGTH).map_err(|error| match error {
+            CustomNameError::InvalidName(set) => CustomRoleSetError::InvalidName { set },
+            CustomNameError::DuplicateName(set) => CustomRoleSetError::DuplicateName { set },
+        })?;
+        for (index, custom_role_set) in custom_role_sets.iter().enumerate() {
+            let set = index as u8;
+            if custom_role_set.roles.is_empty() {
+                return Err(CustomRoleSetError::EmptySet { set });
+            }
+            for (role_index, role) in custom_role_set.roles.iter().enumerate() {
+                if custom_role_set.roles[..role_index].contains(role) {
+                    return Err(CustomRoleSetError::DuplicateRole { set, role: *role });
+                }
+                if !self.enabled_roles.contains(role) {
+                    return Err(CustomRoleSetError::RoleNotEnabled { set, role: *role });
+                }
+            }
+        }
+        Ok(())
+    }
+    /// Constraints name their custom role sets the same way role list options do
+    pub fn check_role_list_constraints(&self, constraints: &[RoleListConstraint])->Result<(), RoleListConstraintError>{
+        if constraints.len() > RoleListConstraint::MAX_COUNT {
+            return Err(RoleListConstraintError::TooManyConstraints);
+        }
+        for (index, constraint) in constraints.iter().enumerate() {
+            let constraint_index = index as u8;
+            let roles = constraint.roles();
+
+            if let RoleOutlineOptionRoles::CustomRoleSet { custom_role_set } = roles {
+                if !self.custom_role_sets.iter().any(|set| set.name == *custom_role_set) {
+                    return Err(RoleListConstraintError::UnknownCustomRoleSet { constraint: constraint_index });
+                }
+            }
+            if roles.get_roles(&self.custom_role_sets).is_empty() {
+                return Err(RoleListConstraintError::NoRoles { constraint: constraint_index });
+            }
+            if let RoleListConstraint::AtMost { count, .. } = constraint {
+                if *count as usize > self.role_list.0.len() {
+                    return Err(RoleListConstraintError::CountLargerThanRoleList { constraint: constraint_index });
+                }
+            }
+        }
+        Ok(())
+    }
+    /// Role list options refer to custom insider groups by index,
+    /// so options for groups that don't exist are ignored when the game starts
+    pub fn check_custom_insider_groups(custom_insider_groups: &[CustomInsiderGroup])->Result<(), CustomInsiderGroupError>{
+        if custom_insider_groups.len() > CustomInsiderGroup::MAX_COUNT {
+            return Err(CustomInsiderGroupError::TooManyGroups);
+        }
+        let names: Vec<&str> = custom_insider_groups.iter().map(|group| group.name.as_str()).collect();
+        check_custom_names(&names, CustomInsiderGroup::MAX_NAME_LENGTH).map_err(|error| match error {
+            CustomNameError::InvalidName(group) => CustomInsiderGroupError::InvalidName { group },
+            CustomNameError::DuplicateName(group) => CustomInsiderGroupError::DuplicateName { group },
+        })
+    }
+    /// Also makes sure role list options still get the groups they were given.
+    /// Renaming a group is fine, but removing or moving one would give its options a different group.
+    pub fn check_custom_insider_group_changes(&self, custom_insider_groups: &[CustomInsiderGroup])->Result<(), CustomInsiderGroupError>{
+        Self::check_custom_insider_groups(custom_insider_groups)?;
+
+        let used_groups = self.role_list_options()
+            .filter_map(|option| match &option.insider_groups {
+                RoleOutlineOptionInsiderGroups::Custom { insider_groups } => Some(insider_groups.iter()),
+                RoleOutlineOptionInsiderGroups::RoleDefault => None,
+            })
+            .flatten()
+            .filter_map(|insider_group| match insider_group {
+                InsiderGroupID::Custom(id) => Some(*id),
+                _ => None
+            });
+        let old_names: Vec<&str> = self.custom_insider_groups.iter().map(|group| group.name.as_str()).collect();
+        let new_names: Vec<&str> = custom_insider_groups.iter().map(|group| group.name.as_str()).collect();
+
+        match moved_custom_reference(&old_names, &new_names, used_groups) {
+            Some(group) => Err(CustomInsiderGroupError::GroupInUse { group }),
+            None => Ok(())
+        }
+    }
+    /// Like custom insider groups, win conditions refer to custom factions by index
+    pub fn check_custom_factions(custom_factions: &[CustomFaction])->Result<(), CustomFactionError>{
+        if custom_factions.len() > CustomFaction::MAX_COUNT {
+            return Err(CustomFactionError::TooManyFactions);
+        }
+        let names: Vec<&str> = custom_factions.iter().map(|faction| faction.name.as_str()).collect();
+        check_custom_names(&names, CustomFaction::MAX_NAME_LENGTH).map_err(|error| match error {
+            CustomNameError::InvalidName(faction) => CustomFactionError::InvalidName { faction },
+            CustomNameError::DuplicateName(faction) => CustomFactionError::DuplicateName { faction },
+        })?;
+        for (index, custom_faction) in custom_factions.iter().enumerate() {
+            let faction = index as u8;
+            for (role_index, role) in custom_faction.keeps_game_running.iter().enumerate() {
+                if custom_faction.keeps_game_running[..role_index].contains(role) {
+                    return Err(CustomFactionError::DuplicateRole { faction, role: *role });
+                }
+            }
+        }
+        Ok(())
+    }
+    /// Also makes sure win conditions still refer to the factions they were given, the same way as custom insider groups
+    pub fn check_custom_faction_changes(&self, custom_factions: &[CustomFaction])->Result<(), CustomFactionError>{
+        Self::check_custom_factions(custom_factions)?;
+
+        let used_factions = self.role_list_options()
+            .filter_map(|option| match &option.win_condition {
+                RoleOutlineOptionWinCondition::GameConclusionReached { win_if_any } => Some(win_if_any.iter()),
+                RoleOutlineOptionWinCondition::RoleDefault => None,
+            })
+            .flatten()
+            .filter_map(|conclusion| match conclusion {
+                GameConclusion::Custom(id) => Some(*id),
+                _ => None
+            });
+        let old_names: Vec<&str> = self.custom_factions.iter().map(|faction| faction.name.as_str()).collect();
+        let new_names: Vec<&str> = custom_factions.iter().map(|faction| faction.name.as_str()).collect();
+
+        match moved_custom_reference(&old_names, &new_names, used_factions) {
+            Some(faction) => Err(CustomFactionError::FactionInUse { faction }),
+            None => Ok(())
+        }
+    }
+    /// Custom role sets can only use enabled roles, so disabling a role takes it out of every set.
+    /// Sets left empty are removed, and options that used them have no roles until the host changes them.
+    pub fn remove_disabled_roles_from_custom_role_sets(&mut self){
+        let enabled_roles = &self.enabled_roles;
+        for custom_role_set in self.custom_role_sets.iter_mut() {
+            custom_role_set.roles.retain(|role| enabled_roles.contains(role));
+        }
+        self.custom_role_sets.retain(|custom_role_set| !custom_role_set.roles.is_empty());
+    }
+    fn role_list_options(&self)->impl Iterator<Item = &RoleOutlineOption>{
+        self.role_list.0.iter().flat_map(|outline| outline.options.iter())
+    }
+    pub fn role_maximum_count(&self, role: Role)->Option<u8>{
+        self.role_configurations.get(&role)
+            .and_then(|configuration| configuration.maximum_count)
+            .or(role.maximum_count())
+    }
+    /// None if the host didn't change the role's default
+    pub fn role_uses(&self, role: Role)->Option<u8>{
+        self.role_configurations.get(&role)
+            .and_then(|configuration| configuration.uses)
+    }
+}
+
+enum CustomNameError {
+    InvalidName(u8),
+    DuplicateName(u8),
+}
+
+/// Custom role sets, custom insider groups and custom factions need trimmed, unique names
+fn check_custom_names(names: &[&str], max_name_length: usize)->Result<(), CustomNameError>{
+    for (index, name) in names.iter().enumerate() {
+        if name.trim().is_empty() || name.trim() != *name || name.chars().count() > max_name_length {
+            return Err(CustomNameError::InvalidName(index as u8));
+        }
+        if names[..index].contains(name) {
+            return Err(CustomNameError::DuplicateName(index as u8));
+        }
+    }
+    Ok(())
+}
+
+/// Role list options refer to custom insider groups and custom factions by their index.
+/// Finds a used index that would now mean something else, because what it meant was removed or moved.
+/// A new name at the same index is a rename, unless that name was already used elsewhere.
+fn moved_custom_reference(old_names: &[&str], new_names: &[&str], used: impl Iterator<Item = u8>)->Option<u8>{
+    used.into_iter().find(|index| {
+        // Options for indices that never existed are ignored, so nothing changes for them
+        let Some(old_name) = old_names.get(*index as usize) else {return false};
+        match new_names.get(*index as usize) {
+            None => true,
+            Some(new_name) => new_name != old_name && (old_names.contains(new_name) || new_names.contains(old_name))
+        }
+    })
+}
+
+/// Saved the same way as the EnabledModifiers packet, since each state already knows its type
+mod enabled_modifiers_as_list {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::{game::modifiers::{ModifierState, ModifierType}, vec_map::VecMap};
+
+    pub fn serialize<S: Serializer>(modifiers: &VecMap<ModifierType, ModifierState>, serializer: S) -> Result<S::Ok, S::Error> {
+        modifiers.values().collect::<Vec<_>>().serialize(serializer)
+    }
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<VecMap<ModifierType, ModifierState>, D::Error> {
+        Ok(VecMap::new_from_vec(
+            Vec::<ModifierState>::deserialize(deserializer)?.into_iter()
+                .map(|state| (ModifierType::from(&state), state))
+                .collect()
+        ))
+    }
+}
+
+/// Host overrides for numbers built into a role, None keeps the role's default
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoleConfiguration{
+    pub maximum_count: Option<u8>,
+    /// How many times the role's limited ability can be used, such as doctor self heals or vigilante bullets
+    pub uses: Option<u8>,
+}
+impl RoleConfiguration{
+    /// Roles that read `uses`
+    pub const ROLES_WITH_USES: [Role; 6] = [
+        Role::Doctor, Role::Bodyguard, Role::Deputy, Role::Jailor, Role::Vigilante, Role::Rabblerouser
+    ];
+
+    pub fn is_valid_for(&self, role: Role)->bool{
+        self.maximum_count.is_none_or(|count| count > 0) &&
+        (self.uses.is_none() || Self::ROLES_WITH_USES.contains(&role))
+    }
+}
+
+/// What spectators can see while the game is running
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SpectatorSettings{
+    /// Spectators see every player's role, every chat, graves before they're obscured, and each night's results
+    pub omniscient_view: bool,
+    /// How long packets to spectators are held back, so they can't tell players what's happening
+    pub stream_delay_seconds: u16,
 }
+impl SpectatorSettings{
+    pub const MAX_STREAM_DELAY_SECONDS: u16 = 600;
+
+    pub fn stream_delay(&self)->Duration{
+        Duration::from_secs(self.stream_delay_seconds.min(Self::MAX_STREAM_DELAY_SECONDS) as u64)
+    }
+}
+
+/// Limits on whispers, checked along with the rules in `Whispers`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WhisperSettings{
+    /// How long a player has to wait after whispering before they can whisper again
+    pub cooldown_seconds: u16,
+    /// Longer whispers are cut off
+    pub max_length: u16,
+    /// Whether everyone is told who whispered to who
+    pub broadcast: bool,
+}
+impl Default for WhisperSettings{
+    fn default() -> Self {
+        Self {
+            cooldown_seconds: 0,
+            max_length: Self::MAX_LENGTH,
+            broadcast: true,
+        }
+    }
+}
+impl WhisperSettings{
+    pub const MAX_COOLDOWN_SECONDS: u16 = 600;
+    pub const MAX_LENGTH: u16 = 600;
+
+    /// Keeps every setting in range, the lobby uses this on whatever the host sends
+    pub fn clamped(self)->Self{
+        Self {
+            cooldown_seconds: self.cooldown_seconds.min(Self::MAX_COOLDOWN_SECONDS),
+            max_length: self.max_length.clamp(1, Self::MAX_LENGTH),
+            ..self
+        }
+    }
+    pub fn cooldown(&self)->Duration{
+        Duration::from_secs(self.cooldown_seconds.min(Self::MAX_COOLDOWN_SECONDS) as u64)
+    }
+    pub fn max_length(&self)->u16{
+        self.max_length.clamp(1, Self::MAX_LENGTH)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PhaseTimeSettings{
@@ -1,9 +1,11 @@
 pub mod spectator_pointer;
 
+use std::{cell::RefCell, collections::VecDeque, time::Duration};
+
 use crate::{
     client_connection::ClientConnection,
     packet::ToClientPacket,
-    game::chat::ChatMessageVariant,
+    game::chat::ChatMessage,
 };
 
 #[derive(Debug, Clone)]
@@ -16,27 +18,58 @@ pub struct Spectator {
     pub host: bool,
     pub fast_forward_vote: bool,
 
-    pub queued_chat_messages: Vec<ChatMessageVariant>,
+    pub queued_chat_messages: Vec<ChatMessage>,
+
+    /// Hosts are never delayed, so they can keep running the game
+    stream_delay: Duration,
+    /// How long this spectator has been watching, used to time delayed packets
+    time_watched: Duration,
+    /// Packets held back by the stream delay, with the time watched when they can be sent.
+    /// Sending only needs a shared reference to the game, so this needs a RefCell.
+    delayed_packets: RefCell<VecDeque<(Duration, ToClientPacket)>>,
 }
 impl Spectator {
-    pub fn new(params: SpectatorInitializeParameters) -> Self {
+    pub fn new(params: SpectatorInitializeParameters, stream_delay: Duration) -> Self {
         Self {
             connection: params.connection,
             host: params.host,
             fast_forward_vote: false,
 
             queued_chat_messages: Vec::new(),
+
+            stream_delay: if params.host { Duration::ZERO } else { stream_delay },
+            time_watched: Duration::ZERO,
+            delayed_packets: RefCell::new(VecDeque::new()),
         }
     }
     pub fn is_host(&self) -> bool {
         self.host
     }
     pub fn send_packet(&self, packet: ToClientPacket) {
-        self.connection.send_packet(packet);
+        if self.stream_delay.is_zero() {
+            self.connection.send_packet(packet);
+        } else {
+            self.delayed_packets.borrow_mut().push_back((self.time_watched + self.stream_delay, packet));
+        }
     }
     pub fn send_packets(&self, packets: Vec<ToClientPacket>) {
         for packet in packets {
             self.send_packet(packet);
         }
     }
-}
\ No newline at end of file
+    /// Sends the delayed packets that have waited long enough
+    pub fn tick(&mut self, time_passed: Duration) {
+        self.time_watched += time_passed;
+
+        let delayed_packets = self.delayed_packets.get_mut();
+        while delayed_packets.front().is_some_and(|(send_at, _)| *send_at <= self.time_watched) {
+            if let Some((_, packet)) = delayed_packets.pop_front() {
+                self.connection.send_packet(packet);
+            }
+        }
+    }
+    /// Anything still delayed is sent again when the spectator reconnects
+    pub fn clear_delayed_packets(&mut self) {
+        self.delayed_packets.get_mut().clear();
+    }
+}
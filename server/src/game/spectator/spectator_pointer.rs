@@ -1,27 +1,29 @@
 use std::time::Duration;
 
 use crate::{
-    client_connection::ClientConnection, game::{chat::{ChatGroup, ChatMessage}, phase::PhaseState, player::PlayerReference, Game, GameOverReason}, packet::ToClientPacket
+    client_connection::ClientConnection, game::{components::night_resolution_trace::NightResolutionTrace, phase::PhaseState, player::PlayerReference, Game, GameOverReason}, lobby::GAME_DISCONNECT_TIMER_SECS, packet::ToClientPacket, websocket_connections::connection::ClientSender
 };
 
 use super::Spectator;
 
-pub type SpectatorIndex = u8;
+/// Spectators keep their ID until they leave, and IDs are never reused in a game
+pub type SpectatorID = u32;
 ///
 /// This does not guarantee that the spectator exists
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct SpectatorPointer {
-    pub index: SpectatorIndex,
+    pub id: SpectatorID,
 }
 impl SpectatorPointer {
-    pub fn new(index: SpectatorIndex) -> Self {
-        SpectatorPointer { index }
+    pub fn new(id: SpectatorID) -> Self {
+        SpectatorPointer { id }
     }
 
     pub fn deref_mut<'a>(&self, game: &'a mut Game)->Option<&'a mut Spectator>{
-        game.spectators.get_mut(self.index as usize)
+        game.spectators.get_mut(&self.id)
     }
     pub fn deref<'a>(&self, game: &'a Game)->Option<&'a Spectator>{
-        game.spectators.get(self.index as usize)
+        game.spectators.get(&self.id)
     }
 
     pub fn host(&self, game: &Game)->bool {
@@ -30,6 +32,20 @@ impl SpectatorPointer {
     pub fn connection(&self, game: &Game) -> ClientConnection {
         self.deref(game).map(|s|s.connection.clone()).unwrap_or(ClientConnection::Disconnected)
     }
+    pub fn could_reconnect(&self, game: &Game) -> bool {
+        matches!(self.connection(game), ClientConnection::CouldReconnect { .. })
+    }
+
+    pub fn connect(&self, game: &mut Game, sender: ClientSender){
+        let Some(s) = self.deref_mut(game) else {return};
+        s.connection = ClientConnection::Connected(sender);
+        s.clear_delayed_packets();
+        self.send_join_game_data(game);
+    }
+    pub fn lose_connection(&self, game: &mut Game){
+        let Some(s) = self.deref_mut(game) else {return};
+        s.connection = ClientConnection::CouldReconnect { disconnect_timer: Duration::from_secs(GAME_DISCONNECT_TIMER_SECS) };
+    }
 
     pub fn send_packet(&self, game: &Game, packet: ToClientPacket){
         if let Some(s) = self.deref(game) { 
@@ -42,23 +58,31 @@ impl SpectatorPointer {
         }
     }
 
-    pub fn all_spectators(game: &Game) -> SpectatorPointerIterator {
-        SpectatorPointerIterator {
-            current: 0,
-            end: game.spectators.len() as SpectatorIndex
-        }
+    pub fn all_spectators(game: &Game) -> std::vec::IntoIter<SpectatorPointer> {
+        game.spectators.keys()
+            .map(|id| SpectatorPointer::new(*id))
+            .collect::<Vec<_>>()
+            .into_iter()
     }
 
 
-    pub fn tick(&self, game: &mut Game, _time_passed: Duration){
+    pub fn tick(&self, game: &mut Game, time_passed: Duration){
 
         let s = match self.deref_mut(game){
             Some(s) => s,
             None => return
         };
-
-        if let ClientConnection::Connected(_) = s.connection {
-            self.send_repeating_data(game)
+        s.tick(time_passed);
+
+        match s.connection {
+            ClientConnection::Connected(_) => self.send_repeating_data(game),
+            ClientConnection::CouldReconnect { disconnect_timer } => {
+                match disconnect_timer.saturating_sub(time_passed) {
+                    Duration::ZERO => game.remove_spectator(self.id),
+                    time_remaining => s.connection = ClientConnection::CouldReconnect { disconnect_timer: time_remaining }
+                }
+            },
+            ClientConnection::Disconnected => {}
         }
     }
     pub fn send_repeating_data(&self, game: &mut Game){
@@ -72,9 +96,8 @@ impl SpectatorPointer {
             },
             ToClientPacket::EnabledRoles { roles: game.settings.enabled_roles.clone().into_iter().collect() },
             ToClientPacket::RoleList {role_list: game.settings.role_list.clone()},
-            ToClientPacket::EnabledModifiers {
-                modifiers: game.settings.enabled_modifiers.clone().into_iter().collect()
-            },
+            ToClientPacket::new_enabled_modifiers(&game.settings),
+            ToClientPacket::new_modifier_parameters(&game.settings),
             ToClientPacket::PlayerAlive{
                 alive: PlayerReference::all_players(game).map(|p|p.alive(game)).collect()
             },
@@ -84,7 +107,12 @@ impl SpectatorPointer {
         ]);
 
         if !game.ticking {
-            self.send_packet(game, ToClientPacket::GameOver { reason: GameOverReason::Draw })
+            self.send_packets(game, game.game_over_packets(GameOverReason::Draw));
+        }else if game.settings.spectator_settings.omniscient_view {
+            self.send_packet(game, ToClientPacket::NightResolutionTrace { nights: NightResolutionTrace::nights(game).clone() });
+        }
+        if game.settings.spectator_settings.omniscient_view {
+            self.send_packet(game, ToClientPacket::new_player_roles(game));
         }
 
         if let PhaseState::Testimony { player_on_trial, .. }
@@ -114,6 +142,7 @@ impl SpectatorPointer {
         self.send_packet(game, ToClientPacket::GameInitializationComplete);
     }
 
+    /// Replaces anything still queued, so reconnecting doesn't send messages twice
     pub fn requeue_chat_messages(&self, game: &mut Game){
         let msgs = game.spectator_chat_messages.clone();
 
@@ -122,9 +151,7 @@ impl SpectatorPointer {
             None=> return
         };
 
-        for msg in msgs.into_iter(){
-            s.queued_chat_messages.push(msg);
-        }
+        s.queued_chat_messages = msgs;
     }
 
     pub fn send_chat_messages(&self, game: &mut Game){
@@ -149,40 +176,9 @@ impl SpectatorPointer {
             }else{ break; }
         }
         
-        self.send_packet(game, ToClientPacket::AddChatMessages { chat_messages: chat_messages_out
-                .into_iter()
-                .map(|p|ChatMessage::new_non_private(p, ChatGroup::All))
-                .collect() 
-            }
-        );
+        self.send_packet(game, ToClientPacket::AddChatMessages { chat_messages: chat_messages_out });
         
 
         self.send_chat_messages(game);
     }
 }
-
-pub struct SpectatorPointerIterator {
-    current: u8,
-    end: u8
-}
-
-impl Iterator for SpectatorPointerIterator {
-    type Item = SpectatorPointer;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.current >= self.end {
-            None
-        } else {
-            let ret = SpectatorPointer::new(self.current);
-            self.current += 1;
-            Some(ret)
-        }
-    }
-
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        let size = (self.end - self.current) as usize;
-        (size, Some(size))
-    }
-}
-
-impl ExactSizeIterator for SpectatorPointerIterator {}
\ No newline at end of file
@@ -0,0 +1,49 @@
+use crate::vec_map::VecMap;
+
+use super::{components::enfranchised::Enfranchised, modifiers::Modifiers, player::PlayerReference, Game};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum VoteType{
+    Nomination,
+    Verdict
+}
+
+/// Every player's vote counts once unless a role, component or modifier changes it.
+/// Each hook receives the weight calculated so far and returns the new weight.
+pub struct VoteWeight;
+impl VoteWeight{
+    pub const DEFAULT: u8 = 1;
+
+    pub fn get(game: &Game, voter: PlayerReference, vote_type: VoteType)->u8{
+        let mut weight = Self::DEFAULT;
+
+        for player_ref in PlayerReference::all_players(game){
+            weight = player_ref.vote_weight(game, voter, vote_type, weight);
+        }
+
+        weight = Enfranchised::vote_weight(game, voter, vote_type, weight);
+        weight = Modifiers::vote_weight(game, voter, vote_type, weight);
+
+        weight
+    }
+
+    /// The weighted number of nomination votes each player has received from living players
+    pub fn nomination_votes(game: &Game)->VecMap<PlayerReference, u8>{
+        let mut voted_player_votes: VecMap<PlayerReference, u8> = VecMap::new();
+
+        for voter in PlayerReference::all_players(game){
+            if !voter.alive(game) { continue }
+            let Some(voted_player) = voter.chosen_vote(game) else { continue };
+
+            let weight = Self::get(game, voter, VoteType::Nomination);
+
+            if let Some(num_votes) = voted_player_votes.get_mut(&voted_player) {
+                *num_votes = num_votes.saturating_add(weight);
+            } else {
+                voted_player_votes.insert(voted_player, weight);
+            }
+        }
+
+        voted_player_votes
+    }
+}
@@ -0,0 +1,92 @@
+use std::time::Duration;
+
+use crate::{strings::TidyableString, vec_map::VecMap, vec_set::VecSet};
+
+use super::{
+    chat::{ChatGroup, ChatMessageVariant}, modifiers::Modifiers,
+    player::{PlayerIndex, PlayerReference}, Game
+};
+
+/// Decides who can whisper to whom, and who else reads each whisper.
+/// The base rules are checked here, then roles and modifiers can forbid a whisper or read it,
+/// each hook receiving whether the whisper is allowed so far and returning the new answer.
+#[derive(Default, Clone)]
+pub struct Whispers{
+    /// How long until each player can whisper again
+    cooldowns: VecMap<PlayerReference, Duration>,
+}
+impl Whispers{
+    pub fn send(game: &mut Game, whisperer: PlayerReference, whisperee_index: PlayerIndex, text: String){
+        let Ok(whisperee) = PlayerReference::new(game, whisperee_index) else {
+            whisperer.add_private_chat_message(game, ChatMessageVariant::InvalidWhisper);
+            return
+        };
+
+        if
+            !Self::can_whisper(game, whisperer, whisperee) ||
+            text.replace(['\n', '\r'], "").trim().is_empty() ||
+            Self::cooldown_remaining(game, whisperer).is_some()
+        {
+            whisperer.add_private_chat_message(game, ChatMessageVariant::InvalidWhisper);
+            return
+        }
+
+        let Some(text) = game.settings.chat_filter.apply(&text) else {
+            whisperer.add_private_chat_message(game, ChatMessageVariant::BlockedByChatFilter);
+            return
+        };
+
+        let whisper_settings = game.settings.whisper_settings.clone();
+        if whisper_settings.broadcast {
+            game.add_message_to_chat_group(ChatGroup::All, ChatMessageVariant::BroadcastWhisper {
+                whisperer: whisperer.index(),
+                whisperee: whisperee.index()
+            });
+        }
+        let message = ChatMessageVariant::Whisper {
+            from_player_index: whisperer.index(),
+            to_player_index: whisperee.index(),
+            text: text.truncate(whisper_settings.max_length() as usize)
+        };
+
+        let mut readers: VecSet<PlayerReference> = vec![whisperer, whisperee].into_iter().collect();
+        readers.extend(Self::extra_readers(game, whisperer, whisperee));
+        game.add_private_message_to_players(readers, message);
+
+        let cooldown = whisper_settings.cooldown();
+        if !cooldown.is_zero() {
+            game.whispers.cooldowns.insert(whisperer, cooldown);
+        }
+    }
+
+    pub fn can_whisper(game: &Game, whisperer: PlayerReference, whisperee: PlayerReference)->bool{
+        let mut allowed =
+            game.current_phase().is_day() &&
+            whisperee.alive(game) == whisperer.alive(game) &&
+            whisperee != whisperer &&
+            whisperer.get_current_send_chat_groups(game).contains(&ChatGroup::All);
+
+        for player_ref in PlayerReference::all_players(game){
+            allowed = player_ref.can_whisper(game, whisperer, whisperee, allowed);
+        }
+        Modifiers::can_whisper(game, whisperer, whisperee, allowed)
+    }
+
+    /// Players other than the whisperer and whisperee who read the whisper
+    pub fn extra_readers(game: &Game, whisperer: PlayerReference, whisperee: PlayerReference)->VecSet<PlayerReference>{
+        PlayerReference::all_players(game)
+            .filter(|player_ref| player_ref.reads_whisper(game, whisperer, whisperee))
+            .collect()
+    }
+
+    pub fn cooldown_remaining(game: &Game, whisperer: PlayerReference)->Option<Duration>{
+        game.whispers.cooldowns.get(&whisperer).copied()
+    }
+
+    pub fn tick(game: &mut Game, time_passed: Duration){
+        for (_, remaining) in game.whispers.cooldowns.iter_mut() {
+            *remaining = remaining.saturating_sub(time_passed);
+        }
+        game.whispers.cooldowns.retain(|_, remaining| !remaining.is_zero());
+    }
+}
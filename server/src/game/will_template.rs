@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{lang::{serde_name, Lang}, strings::TidyableString};
+
+use super::{player::{PlayerIndex, PlayerReference}, role::Role, Game};
+
+/// A will filled in from a template instead of typed out.
+/// The server writes it out as the player's will, so graves, forgers and the will history
+/// all keep working with plain text. New templates are new variants.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[serde(tag = "type")]
+pub enum WillTemplate{
+    /// A role claim, what the player did each night, and anything else
+    #[serde(rename_all = "camelCase")]
+    RoleClaim{
+        role: Option<Role>,
+        /// The first entry is night 1
+        nights: Vec<String>,
+        notes: String
+    },
+    /// What role other players claimed or are thought to be
+    #[serde(rename_all = "camelCase")]
+    PlayerClaims{
+        claims: Vec<PlayerClaim>
+    },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayerClaim{
+    pub player: PlayerIndex,
+    pub role: Option<Role>,
+    pub note: String,
+}
+
+impl WillTemplate{
+    pub const MAX_ENTRIES: usize = 30;
+    pub const MAX_ENTRY_LENGTH: usize = 300;
+
+    /// Players are written as mentions, so every client shows their names.
+    /// Roles use the english names, the same as roles typed into a will.
+    pub fn to_will(&self, game: &Game)->String{
+        let lang = Lang::en_us();
+        let role_name = |role: &Option<Role>| match role {
+            Some(role) => lang.translate(&format!("role.{}.name", serde_name(role)), &[]),
+            None => "?".to_string(),
+        };
+
+        let lines: Vec<String> = match self {
+            WillTemplate::RoleClaim { role, nights, notes } => {
+                std::iter::once(lang.translate("willTemplate.role", &[role_name(role)]))
+                    .chain(nights.iter().take(Self::MAX_ENTRIES).enumerate().map(|(index, entry)|
+                        lang.translate("willTemplate.night", &[(index + 1).to_string(), Self::tidy(entry)])
+                    ))
+                    .chain(Some(Self::tidy(notes)).filter(|notes| !notes.is_empty()))
+                    .collect()
+            },
+            WillTemplate::PlayerClaims { claims } => claims.iter()
+                .filter(|claim| PlayerReference::new(game, claim.player).is_ok())
+                .take(Self::MAX_ENTRIES)
+                .map(|claim| lang.translate("willTemplate.playerClaim", &[
+                    format!("@{}", claim.player as usize + 1), role_name(&claim.role), Self::tidy(&claim.note)
+                ]).trim_end().to_string())
+                .collect(),
+        };
+        lines.join("\n")
+    }
+
+    fn tidy(entry: &str)->String{
+        entry.to_string().remove_newline().trim_whitespace().truncate(Self::MAX_ENTRY_LENGTH)
+    }
+}
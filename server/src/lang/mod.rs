@@ -0,0 +1,55 @@
+pub mod text_renderer;
+
+use std::collections::HashMap;
+
+use lazy_static::lazy_static;
+use serde::Serialize;
+
+pub use text_renderer::TextRenderer;
+
+lazy_static!(
+    static ref EN_US: Lang = Lang::from_json(
+        include_str!("../../../client/src/resources/lang/en_us.json")
+    ).expect("the english lang file should be valid");
+);
+
+/// A locale table, the same flat key to string map the client's lang files use.
+/// Values are filled into `\0`, `\1`, ... in the order they're given.
+#[derive(Debug, Clone, Default)]
+pub struct Lang {
+    translations: HashMap<String, String>
+}
+impl Lang {
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        Ok(Self { translations: serde_json::from_str(json)? })
+    }
+    /// The client's english translations, built into the server
+    pub fn en_us() -> &'static Lang {
+        &EN_US
+    }
+
+    pub fn translate_checked(&self, key: &str, values: &[String]) -> Option<String> {
+        let mut out = self.translations.get(key)?.clone();
+        for (index, value) in values.iter().enumerate() {
+            out = out.replacen(&format!("\\{index}"), value, 1);
+        }
+        Some(out)
+    }
+    /// Falls back to the key itself, so a missing translation still says what it was
+    pub fn translate(&self, key: &str, values: &[String]) -> String {
+        self.translate_checked(key, values).unwrap_or_else(|| key.to_string())
+    }
+}
+
+/// The name the client sees for a value, which is how lang keys are built.
+/// This is the variant name of a unit enum, or the `type` tag of a tagged one.
+pub fn serde_name<T: Serialize>(value: &T) -> String {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(name)) => name,
+        Ok(serde_json::Value::Object(map)) => match map.get("type") {
+            Some(serde_json::Value::String(name)) => name.clone(),
+            _ => map.keys().next().cloned().unwrap_or_default()
+        },
+        _ => String::new()
+    }
+}
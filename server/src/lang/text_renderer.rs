@@ -0,0 +1,571 @@
+use crate::game::{
+    ability_input::{AbilitySelection, ControllerID},
+    chat::{ChatMessageVariant, MessageSender},
+    components::synopsis::Synopsis,
+    game_conclusion::GameConclusion,
+    grave::{Grave, GraveDeathCause, GraveInformation, GraveKiller, GravePhase},
+    modifiers::{ModifierState, ModifierType},
+    phase::PhaseState,
+    player::{PlayerIndex, PlayerReference},
+    role::{auditor::AuditorResult, kira::{KiraGuess, KiraGuessResult}, Role},
+    role_list::{RoleOutline, RoleOutlineOptionRoles, RoleOutlineOptionWinCondition},
+    role_outline_reference::RoleOutlineReference,
+    settings::Settings,
+    win_condition::WinCondition,
+    Game
+};
+
+use super::{serde_name, Lang};
+
+/// Turns chat messages, graves and synopses into plain text, the way the client shows them.
+/// Used for exports, logs and terminal clients.
+///
+/// Without settings, custom factions and role list outlines are shown by their index,
+/// and trial verdicts assume a simple majority.
+pub struct TextRenderer<'a> {
+    lang: &'a Lang,
+    player_names: Vec<String>,
+    settings: Option<&'a Settings>,
+}
+impl<'a> TextRenderer<'a> {
+    pub fn new(lang: &'a Lang, player_names: Vec<String>) -> Self {
+        Self { lang, player_names, settings: None }
+    }
+    pub fn from_game(lang: &'a Lang, game: &'a Game) -> Self {
+        Self {
+            lang,
+            player_names: PlayerReference::all_players(game).map(|player| player.name(game).clone()).collect(),
+            settings: Some(&game.settings),
+        }
+    }
+    pub fn with_settings(mut self, settings: &'a Settings) -> Self {
+        self.settings = Some(settings);
+        self
+    }
+
+    pub fn chat_message_variant(&self, message: &ChatMessageVariant) -> String {
+        match message {
+            ChatMessageVariant::LobbyMessage { sender, text } => format!("{sender}: {text}"),
+            ChatMessageVariant::Normal { message_sender, text, .. } => {
+                let sender = match message_sender {
+                    MessageSender::Player { player } | MessageSender::LivingToDead { player } => Some(self.player(*player)),
+                    MessageSender::Jailor => Some(self.role(Role::Jailor)),
+                    MessageSender::Reporter => Some(self.role(Role::Reporter)),
+                    MessageSender::Spectator { .. } => None,
+                };
+                match sender {
+                    Some(sender) => format!("{sender}: {}", self.replace_mentions(text)),
+                    None => self.replace_mentions(text),
+                }
+            },
+            ChatMessageVariant::Whisper { from_player_index, to_player_index, text } =>
+                self.translate("chatMessage.whisper", &[
+                    self.player(*from_player_index), self.player(*to_player_index), self.replace_mentions(text)
+                ]),
+            ChatMessageVariant::BroadcastWhisper { whisperer, whisperee } =>
+                self.translate("chatMessage.broadcastWhisper", &[self.player(*whisperer), self.player(*whisperee)]),
+            ChatMessageVariant::RoleAssignment { role } =>
+                self.translate("chatMessage.roleAssignment", &[self.role(*role)]),
+            ChatMessageVariant::PlayerDied { grave } => {
+                self.translate("chatMessage.playerDied", &[self.player(grave.player.index()), self.grave_role(grave)])
+                    + "\n" + &self.grave(grave)
+            },
+            ChatMessageVariant::PlayersRoleRevealed { player, role } =>
+                self.translate("chatMessage.playersRoleRevealed", &[self.player(*player), self.role(*role)]),
+            ChatMessageVariant::PlayersRoleConcealed { player } =>
+                self.translate("chatMessage.playersRoleConcealed", &[self.player(*player)]),
+            ChatMessageVariant::TagAdded { player, tag } | ChatMessageVariant::TagRemoved { player, tag } => {
+                let key = if matches!(message, ChatMessageVariant::TagAdded { .. }) {"chatMessage.tagAdded"} else {"chatMessage.tagRemoved"};
+                let tag = serde_name(tag);
+                self.translate(key, &[
+                    self.player(*player),
+                    self.translate(&format!("tag.{tag}.name"), &[]),
+                    self.translate(&format!("tag.{tag}"), &[])
+                ])
+            },
+            ChatMessageVariant::GameOver { synopsis } => self.synopsis(synopsis),
+            ChatMessageVariant::PlayerQuit { player_index, game_over } => {
+                let key = if *game_over {"chatMessage.playerQuit.gameOver"} else {"chatMessage.playerQuit"};
+                self.translate(key, &[self.player(*player_index)])
+            },
+            ChatMessageVariant::PhaseChange { phase, day_number } => self.phase_change(phase, *day_number),
+            ChatMessageVariant::TrialInformation { required_votes, trials_left } =>
+                self.translate("chatMessage.trialInformation", &[required_votes.to_string(), trials_left.to_string()]),
+            ChatMessageVariant::Voted { voter, votee } => match votee {
+                Some(votee) => self.translate("chatMessage.voted", &[self.player(*voter), self.player(*votee)]),
+                None => self.translate("chatMessage.voted.cleared", &[self.player(*voter)]),
+            },
+            ChatMessageVariant::PlayerNominated { player_index, players_voted } =>
+                self.translate("chatMessage.playerNominated", &[self.player(*player_index), self.player_list(players_voted)]),
+            ChatMessageVariant::JudgementVerdict { voter_player_index, verdict } =>
+                self.translate("chatMessage.judgementVerdict", &[
+                    self.player(*voter_player_index),
+                    self.translate(&format!("verdict.{}", serde_name(verdict)), &[])
+                ]),
+            ChatMessageVariant::TrialVerdict { player_on_trial, innocent, guilty } => {
+                let verdict = if self.verdict_is_guilty(*guilty, *innocent) {"verdict.guilty"} else {"verdict.innocent"};
+                self.translate("chatMessage.trialVerdict", &[
+                    self.player(*player_on_trial), self.translate(verdict, &[]), innocent.to_string(), guilty.to_string()
+                ])
+            },
+            ChatMessageVariant::NominationTieBroken { tied_players, player_index } =>
+                self.translate("chatMessage.nominationTieBroken", &[self.player(*player_index), self.player_list(tied_players)]),
+            ChatMessageVariant::NominationRevote { tied_players } =>
+                self.translate("chatMessage.nominationRevote", &[self.player_list(tied_players)]),
+            ChatMessageVariant::EliminationRunoff { tied_players } =>
+                self.translate("chatMessage.eliminationRunoff", &[self.player_list(tied_players)]),
+            ChatMessageVariant::EliminationResult { eliminated, votes, .. } => match eliminated {
+                Some(eliminated) => self.translate("chatMessage.eliminationResult", &[self.player(*eliminated), votes.to_string()]),
+                None => self.translate("chatMessage.eliminationResult.nobody", &[]),
+            },
+            ChatMessageVariant::AbilityUsed { player, ability_id, selection } =>
+                self.translate("chatMessage.abilityUsed", &[
+                    self.player(*player), self.controller_id(ability_id), self.ability_selection(ability_id, selection)
+                ]),
+            ChatMessageVariant::MayorRevealed { player_index } =>
+                self.translate("chatMessage.mayorRevealed", &[self.player(*player_index)]),
+            ChatMessageVariant::PlayerMuted { player_index, muted } =>
+                self.translate(&format!("chatMessage.playerMuted.{muted}"), &[self.player(*player_index)]),
+            ChatMessageVariant::ReporterReport { report } =>
+                self.translate("chatMessage.reporterReport", &[self.replace_mentions(report)]),
+            ChatMessageVariant::PlayerIsBeingInterviewed { player_index } =>
+                self.translate("chatMessage.playerIsBeingInterviewed", &[self.player(*player_index)]),
+            ChatMessageVariant::JailedTarget { player_index } =>
+                self.translate("chatMessage.jailedTarget", &[self.player(*player_index)]),
+            ChatMessageVariant::JailedSomeone { player_index } =>
+                self.translate("chatMessage.jailedSomeone", &[self.player(*player_index)]),
+            ChatMessageVariant::MediumHauntStarted { medium, player } =>
+                self.translate("chatMessage.mediumHauntStarted", &[self.player(*medium), self.player(*player)]),
+            ChatMessageVariant::DeputyKilled { shot_index } =>
+                self.translate("chatMessage.deputyKilled", &[self.player(*shot_index)]),
+            ChatMessageVariant::WardenPlayersImprisoned { players } =>
+                self.translate("chatMessage.wardenPlayersImprisoned", &[self.player_references(players)]),
+            ChatMessageVariant::PlayerDiedOfABrokenHeart { player, lover } =>
+                self.translate("chatMessage.playerDiedOfBrokenHeart", &[self.player(*player), self.player(*lover)]),
+            ChatMessageVariant::PuppeteerPlayerIsNowMarionette { player } =>
+                self.translate("chatMessage.puppeteerPlayerIsNowMarionette", &[self.player(*player)]),
+            ChatMessageVariant::RecruiterPlayerIsNowRecruit { player } =>
+                self.translate("chatMessage.recruiterPlayerIsNowRecruit", &[self.player(*player)]),
+            ChatMessageVariant::NextSantaAbility { ability } =>
+                self.translate(&format!("chatMessage.nextSantaAbility.{}", serde_name(ability)), &[]),
+            ChatMessageVariant::NextKrampusAbility { ability } =>
+                self.translate(&format!("chatMessage.nextKrampusAbility.{}", serde_name(ability)), &[]),
+            ChatMessageVariant::SantaAddedPlayerToNaughtyList { player } =>
+                self.translate("chatMessage.santaAddedPlayerToNaughtyList", &[self.player(player.index())]),
+            ChatMessageVariant::RoleBlocked { immune } =>
+                self.translate(if *immune {"chatMessage.roleBlocked.immune"} else {"chatMessage.roleBlocked"}, &[]),
+            ChatMessageVariant::SheriffResult { suspicious } =>
+                self.translate(if *suspicious {"chatMessage.sheriffResult.suspicious"} else {"chatMessage.sheriffResult.innocent"}, &[]),
+            ChatMessageVariant::LookoutResult { players } =>
+                self.translate("chatMessage.lookoutResult", &[self.player_list(players)]),
+            ChatMessageVariant::TrackerResult { players } =>
+                self.translate("chatMessage.trackerResult", &[self.player_list(players)]),
+            ChatMessageVariant::SeerResult { enemies } =>
+                self.translate(if *enemies {"chatMessage.seerResult.enemies"} else {"chatMessage.seerResult.friends"}, &[]),
+            ChatMessageVariant::SpyMafiaVisit { players } =>
+                self.translate("chatMessage.spyMafiaVisit", &[self.player_list(players)]),
+            ChatMessageVariant::SpyBug { bug } =>
+                self.translate(&format!("chatMessage.spyBug.{}", serde_name(bug)), &[]),
+            ChatMessageVariant::PsychicGood { player } =>
+                self.translate("chatMessage.psychicGood", &[self.player(player.index())]),
+            ChatMessageVariant::PsychicEvil { first, second } =>
+                self.translate("chatMessage.psychicEvil", &[self.player(first.index()), self.player(second.index())]),
+            ChatMessageVariant::AuditorResult { role_outline, result } => match result {
+                AuditorResult::One { role } =>
+                    self.translate("chatMessage.auditorResult.one", &[self.role_outline(role_outline), self.role(*role)]),
+                AuditorResult::Two { roles: [first, second] } =>
+                    self.translate("chatMessage.auditorResult.two", &[self.role_outline(role_outline), self.role(*first), self.role(*second)]),
+            },
+            ChatMessageVariant::SnoopResult { townie } =>
+                self.translate(if *townie {"chatMessage.snoopResult.townie"} else {"chatMessage.snoopResult.inconclusive"}, &[]),
+            ChatMessageVariant::GossipResult { enemies } =>
+                self.translate(if *enemies {"chatMessage.gossipResult.enemies"} else {"chatMessage.gossipResult.none"}, &[]),
+            ChatMessageVariant::TallyClerkResult { evil_count } =>
+                self.translate("chatMessage.tallyClerkResult", &[evil_count.to_string()]),
+            ChatMessageVariant::EngineerVisitorsRole { role } =>
+                self.translate("chatMessage.engineerVisitorsRole", &[self.role(*role)]),
+            ChatMessageVariant::TrapState { state } =>
+                self.translate(&format!("chatMessage.trapState.{}", serde_name(state)), &[]),
+            ChatMessageVariant::TrapStateEndOfNight { state } =>
+                self.translate(&format!("chatMessage.trapStateEndOfNight.{}", serde_name(state)), &[]),
+            ChatMessageVariant::GodfatherBackup { backup } => match backup {
+                Some(backup) => self.translate("chatMessage.godfatherBackup", &[self.player(*backup)]),
+                None => self.translate("chatMessage.godfatherBackup.nobody", &[]),
+            },
+            ChatMessageVariant::GodfatherBackupKilled { backup } =>
+                self.translate("chatMessage.godfatherBackupKilled", &[self.player(*backup)]),
+            ChatMessageVariant::PlayerRoleAndAlibi { player, role, will } =>
+                self.translate("chatMessage.playerRoleAndAlibi", &[self.player(player.index()), self.role(*role), self.replace_mentions(will)]),
+            ChatMessageVariant::InformantResult { role, visited_by, visited } =>
+                self.translate("chatMessage.informantResult", &[
+                    self.translate("chatMessage.targetHasRole", &[self.role(*role)]),
+                    self.translate("chatMessage.informantResult.visited", &[self.player_list(visited)]),
+                    self.translate("chatMessage.informantResult.visitedBy", &[self.player_list(visited_by)]),
+                ]),
+            ChatMessageVariant::FramerResult { mafia_member, visitors } => if visitors.is_empty() {
+                self.translate("chatMessage.framerResult.nobody", &[self.player(*mafia_member)])
+            } else {
+                self.translate("chatMessage.framerResult", &[
+                    self.player(*mafia_member),
+                    visitors.iter().map(|role| self.role(*role)).collect::<Vec<_>>().join(", ")
+                ])
+            },
+            ChatMessageVariant::ScarecrowResult { players } =>
+                self.translate("chatMessage.scarecrowResult", &[self.player_list(players)]),
+            ChatMessageVariant::AmbusherCaught { ambusher } =>
+                self.translate("chatMessage.ambusherCaught", &[self.player(ambusher.index())]),
+            ChatMessageVariant::YouWerePossessed { immune } =>
+                self.translate(if *immune {"chatMessage.youWerePossessed.immune"} else {"chatMessage.youWerePossessed"}, &[]),
+            ChatMessageVariant::TargetsMessage { message } =>
+                self.translate("chatMessage.targetsMessage", &[]) + "\n" + &self.chat_message_variant(message),
+            ChatMessageVariant::TargetHasRole { role } =>
+                self.translate("chatMessage.targetHasRole", &[self.role(*role)]),
+            ChatMessageVariant::TargetHasWinCondition { win_condition } =>
+                self.translate("chatMessage.targetHasWinCondition", &[self.win_condition(win_condition)]),
+            ChatMessageVariant::WerewolfTrackingResult { tracked_player, players } =>
+                self.translate("chatMessage.werewolfTrackingResult", &[self.player(*tracked_player), self.player_list(players)]),
+            ChatMessageVariant::YouAreLoveLinked { player } =>
+                self.translate("chatMessage.youAreLoveLinked", &[self.player(*player)]),
+            ChatMessageVariant::ChronokaiserSpeedUp { percent } =>
+                self.translate("chatMessage.chronokaiserSpeedUp", &[percent.to_string()]),
+            ChatMessageVariant::KiraResult { result } =>
+                std::iter::once(self.translate("chatMessage.kiraResult", &[]))
+                    .chain(result.guesses.iter().map(|(player, (guess, result))|
+                        self.kira_guess(player.index(), guess, Some(result))
+                    ))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            ChatMessageVariant::MartyrRevealed { martyr } =>
+                self.translate("chatMessage.martyrRevealed", &[self.player(*martyr)]),
+            ChatMessageVariant::WildcardConvertFailed { role } =>
+                self.translate("chatMessage.wildcardConvertFailed", &[self.role(*role)]),
+
+            ChatMessageVariant::PhaseFastForwarded |
+            ChatMessageVariant::InvalidWhisper |
+            ChatMessageVariant::BlockedByChatFilter |
+            ChatMessageVariant::PoliticianCountdownStarted |
+            ChatMessageVariant::MediumExists |
+            ChatMessageVariant::DeputyShotYou |
+            ChatMessageVariant::YourConvertFailed |
+            ChatMessageVariant::CultConvertsNext |
+            ChatMessageVariant::CultKillsNext |
+            ChatMessageVariant::AddedToNiceList |
+            ChatMessageVariant::AddedToNaughtyList |
+            ChatMessageVariant::SomeoneSurvivedYourAttack |
+            ChatMessageVariant::YouSurvivedAttack |
+            ChatMessageVariant::TargetWasAttacked |
+            ChatMessageVariant::YouWereProtected |
+            ChatMessageVariant::YouDied |
+            ChatMessageVariant::YouWereAttacked |
+            ChatMessageVariant::YouAttackedSomeone |
+            ChatMessageVariant::YouArePoisoned |
+            ChatMessageVariant::Wardblocked |
+            ChatMessageVariant::PsychicFailed |
+            ChatMessageVariant::ArmorsmithArmorBroke |
+            ChatMessageVariant::Transported |
+            ChatMessageVariant::Silenced |
+            ChatMessageVariant::TargetIsPossessionImmune |
+            ChatMessageVariant::JesterWon |
+            ChatMessageVariant::RevolutionaryWon |
+            ChatMessageVariant::DoomsayerWon |
+            ChatMessageVariant::DoomsayerFailed |
+            ChatMessageVariant::MartyrWon |
+            ChatMessageVariant::MartyrFailed => self.translate(&format!("chatMessage.{}", serde_name(message)), &[]),
+        }
+    }
+
+    pub fn grave(&self, grave: &Grave) -> String {
+        let (phase, icon) = match grave.died_phase {
+            GravePhase::Day => ("day", "day.icon"),
+            GravePhase::Night => ("phase.night", "night.icon"),
+        };
+        let mut lines = vec![
+            format!("{}{}{}", self.translate(phase, &[]), self.translate(icon, &[]), grave.day_number),
+            format!("{} ({})", self.player(grave.player.index()), self.grave_role(grave)),
+        ];
+
+        if let GraveInformation::Normal { will, death_cause, death_notes, .. } = &grave.information {
+            let death_cause = match death_cause {
+                GraveDeathCause::None => None,
+                GraveDeathCause::Killers(killers) => Some(
+                    killers.iter().map(|killer| self.grave_killer(killer)).collect::<Vec<_>>().join(", ") + "."
+                ),
+                _ => Some(self.translate(&format!("grave.deathCause.{}", serde_name(death_cause)), &[])),
+            };
+            if let Some(death_cause) = death_cause {
+                lines.push(format!("{} {}", self.translate("killedBy", &[]), death_cause));
+            }
+            if !will.is_empty() {
+                lines.push(self.translate("alibi", &[]));
+                lines.push(self.replace_mentions(will));
+            }
+            for note in death_notes {
+                lines.push(self.translate("grave.deathNote", &[]));
+                lines.push(self.replace_mentions(note));
+            }
+        }
+
+        lines.join("\n")
+    }
+
+    pub fn synopsis(&self, synopsis: &Synopsis) -> String {
+        let conclusion = self.lang.translate_checked(&format!("chatMessage.gameOver.conclusion.{}", serde_name(synopsis.conclusion())), &[])
+            .filter(|_| !matches!(synopsis.conclusion(), GameConclusion::Custom(_)))
+            .unwrap_or_else(|| self.translate("chatMessage.gameOver.conclusion.unknown", &[self.conclusion(synopsis.conclusion())]));
+
+        std::iter::once(conclusion)
+            .chain(synopsis.player_synopses().iter().enumerate().map(|(index, player_synopsis)| {
+                let crumbs = player_synopsis.crumbs().iter()
+                    .map(|crumb| self.translate("chatMessage.gameOver.player.crumb", &[
+                        self.win_condition(crumb.win_condition()), self.role(crumb.role())
+                    ]))
+                    .collect::<Vec<_>>()
+                    .join(" → ");
+
+                format!("{} ({crumbs})", self.translate(
+                    &format!("chatMessage.gameOver.player.won.{}", player_synopsis.won()),
+                    &[self.player(index as PlayerIndex)]
+                ))
+            }))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn phase_change(&self, phase: &PhaseState, day_number: u8) -> String {
+        let phase_name = self.translate(&format!("phase.{}", serde_name(phase)), &[]);
+        match phase {
+            PhaseState::Nomination { trials_left, .. } =>
+                self.translate("chatMessage.phaseChange.nomination", &[phase_name, day_number.to_string(), trials_left.to_string()]),
+            PhaseState::Testimony { player_on_trial, .. } |
+            PhaseState::Judgement { player_on_trial, .. } |
+            PhaseState::FinalWords { player_on_trial } =>
+                self.translate("chatMessage.phaseChange.trial", &[phase_name, day_number.to_string(), self.player(player_on_trial.index())]),
+            PhaseState::Recess => self.translate("chatMessage.phaseChange.recess", &[]),
+            PhaseState::Briefing |
+            PhaseState::Obituary |
+            PhaseState::Discussion |
+            PhaseState::Dusk |
+            PhaseState::Night => self.translate("chatMessage.phaseChange", &[phase_name, day_number.to_string()]),
+        }
+    }
+
+    fn ability_selection(&self, ability_id: &ControllerID, selection: &AbilitySelection) -> String {
+        match selection {
+            AbilitySelection::Unit => self.translate("chatMessage.abilityUsed.selection.unit", &[]),
+            AbilitySelection::Boolean { selection } => {
+                let text = self.lang.translate_checked(&format!("controllerId.{}.boolean.{}", Self::controller_id_key(ability_id), selection.0), &[])
+                    .unwrap_or_else(|| self.translate(if selection.0 {"on"} else {"off"}, &[]));
+                self.translate("chatMessage.abilityUsed.selection.boolean", &[format!(" {text}")])
+            },
+            AbilitySelection::TwoPlayerOption { selection } => {
+                let players = selection.0.map(|(first, second)| vec![first, second]).unwrap_or_default();
+                self.translate("chatMessage.abilityUsed.selection.twoPlayerOption", &[self.player_references(&players)])
+            },
+            AbilitySelection::PlayerList { selection } =>
+                self.translate("chatMessage.abilityUsed.selection.playerList", &[self.player_references(&selection.0)]),
+            AbilitySelection::RoleOption { selection } =>
+                self.translate("chatMessage.abilityUsed.selection.roleOption", &[self.optional_role(selection.0)]),
+            AbilitySelection::TwoRoleOption { selection } =>
+                self.translate("chatMessage.abilityUsed.selection.twoRoleOption", &[
+                    self.optional_role(selection.0), self.optional_role(selection.1)
+                ]),
+            AbilitySelection::TwoRoleOutlineOption { selection } => {
+                let outline = |outline: &Option<RoleOutlineReference>| match outline {
+                    None => self.translate("none", &[]),
+                    Some(outline) => {
+                        let index = outline.index();
+                        match self.settings.and_then(|settings| settings.role_list.0.get(index as usize)) {
+                            Some(role_outline) => self.role_outline(role_outline),
+                            None => index.to_string(),
+                        }
+                    }
+                };
+                self.translate("chatMessage.abilityUsed.selection.twoRoleOutlineOption", &[
+                    outline(&selection.0), outline(&selection.1)
+                ])
+            },
+            AbilitySelection::String { selection } =>
+                self.translate("chatMessage.abilityUsed.selection.string", &[self.replace_mentions(&selection.0)]),
+            AbilitySelection::Integer { selection } => {
+                let text = self.lang.translate_checked(&format!("controllerId.{}.integer.{}", Self::controller_id_key(ability_id), selection.0), &[])
+                    .unwrap_or_else(|| selection.0.to_string());
+                self.translate("chatMessage.abilityUsed.selection.integer", &[text])
+            },
+            AbilitySelection::Kira { selection } =>
+                std::iter::once(self.translate("chatMessage.kiraSelection", &[]))
+                    .chain(selection.0.iter().map(|(player, guess)| self.kira_guess(player.index(), guess, None)))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+        }
+    }
+
+    fn controller_id(&self, ability_id: &ControllerID) -> String {
+        match ability_id {
+            ControllerID::Role { role, id, .. } => format!("{} {}",
+                self.role(*role),
+                self.lang.translate_checked(&format!("controllerId.role.{}.{id}.name", serde_name(role)), &[]).unwrap_or_default()
+            ),
+            _ => self.lang.translate_checked(&format!("controllerId.{}.name", serde_name(ability_id)), &[]).unwrap_or_default(),
+        }
+    }
+    /// The controller's part of its lang keys, like `role.jailor.0`
+    fn controller_id_key(ability_id: &ControllerID) -> String {
+        match ability_id {
+            ControllerID::Role { role, id, .. } => format!("role.{}.{id}", serde_name(role)),
+            _ => serde_name(ability_id),
+        }
+    }
+
+    fn kira_guess(&self, player: PlayerIndex, guess: &KiraGuess, result: Option<&KiraGuessResult>) -> String {
+        let guess_name = serde_name(guess);
+        let mut out = format!("{} {}",
+            self.player(player),
+            self.lang.translate_checked(&format!("role.{guess_name}.name"), &[])
+                .unwrap_or_else(|| self.translate(&guess_name, &[]))
+        );
+        if let Some(result) = result.filter(|_| *guess != KiraGuess::None) {
+            let icon = match result {
+                KiraGuessResult::Correct => "🟩",
+                KiraGuessResult::WrongSpot => "🟨",
+                KiraGuessResult::NotInGame => "🟥",
+            };
+            out += &format!(" {icon} {}", self.translate(&format!("kiraResult.{}", serde_name(result)), &[]));
+        }
+        out
+    }
+
+    fn grave_role(&self, grave: &Grave) -> String {
+        match grave.role() {
+            Some(role) => self.role(role),
+            None => self.translate("obscured", &[]),
+        }
+    }
+    fn grave_killer(&self, killer: &GraveKiller) -> String {
+        match killer {
+            GraveKiller::Role(role) => self.role(*role),
+            GraveKiller::RoleSet(role_set) => self.translate(&serde_name(role_set), &[]),
+            GraveKiller::CustomRoleSet(name) => name.clone(),
+            GraveKiller::Suicide | GraveKiller::Quit => self.translate(&format!("grave.killer.{}", serde_name(killer)), &[]),
+        }
+    }
+
+    fn role_outline(&self, role_outline: &RoleOutline) -> String {
+        role_outline.options.iter()
+            .map(|option| {
+                let win_condition = match &option.win_condition {
+                    RoleOutlineOptionWinCondition::RoleDefault => String::new(),
+                    RoleOutlineOptionWinCondition::GameConclusionReached { win_if_any } =>
+                        self.win_condition(&WinCondition::GameConclusionReached {
+                            win_if_any: win_if_any.iter().cloned().collect()
+                        }) + " ",
+                };
+                let roles = match &option.roles {
+                    RoleOutlineOptionRoles::RoleSet { role_set } => self.translate(&serde_name(role_set), &[]),
+                    RoleOutlineOptionRoles::Role { role } => self.role(*role),
+                    RoleOutlineOptionRoles::CustomRoleSet { custom_role_set } => custom_role_set.clone(),
+                };
+                win_condition + &roles
+            })
+            .collect::<Vec<_>>()
+            .join(&format!(" {} ", self.translate("union", &[])))
+    }
+
+    fn win_condition(&self, win_condition: &WinCondition) -> String {
+        let WinCondition::GameConclusionReached { win_if_any } = win_condition else {
+            return self.translate("winCondition.independent", &[]);
+        };
+        let evil = [GameConclusion::Mafia, GameConclusion::Fiends, GameConclusion::Cult, GameConclusion::Politician];
+
+        if win_if_any.is_empty() {
+            self.translate("winCondition.loser", &[])
+        } else if win_if_any.len() == 1 {
+            win_if_any.iter().map(|conclusion| self.conclusion(conclusion)).collect()
+        } else if win_if_any.len() == evil.len() && evil.iter().all(|conclusion| win_if_any.contains(conclusion)) {
+            self.translate("winCondition.evil", &[])
+        } else {
+            let mut conclusions: Vec<&GameConclusion> = win_if_any.iter().collect();
+            conclusions.sort();
+            conclusions.into_iter()
+                .map(|conclusion| self.conclusion(conclusion))
+                .collect::<Vec<_>>()
+                .join(&format!(" {} ", self.translate("union", &[])))
+        }
+    }
+    fn conclusion(&self, conclusion: &GameConclusion) -> String {
+        match conclusion {
+            GameConclusion::Politician => self.role(Role::Politician),
+            GameConclusion::Draw => self.translate("winCondition.draw", &[]),
+            GameConclusion::Custom(id) => self.settings
+                .and_then(|settings| settings.custom_factions.get(*id as usize))
+                .map(|faction| faction.name.clone())
+                .unwrap_or_else(|| id.to_string()),
+            _ => self.translate(&serde_name(conclusion), &[]),
+        }
+    }
+
+    fn verdict_is_guilty(&self, guilty: u8, innocent: u8) -> bool {
+        match self.settings.and_then(|settings| settings.enabled_modifiers.get(&ModifierType::TwoThirdsMajority)) {
+            Some(ModifierState::TwoThirdsMajority(majority)) => majority.verdict_is_guilty(guilty, innocent),
+            _ => innocent < guilty
+        }
+    }
+
+    fn role(&self, role: Role) -> String {
+        self.translate(&format!("role.{}.name", serde_name(&role)), &[])
+    }
+    fn optional_role(&self, role: Option<Role>) -> String {
+        match role {
+            Some(role) => self.role(role),
+            None => self.translate("none", &[]),
+        }
+    }
+
+    /// Players without a name are shown the way players mention them, like `@3`
+    fn player(&self, player: PlayerIndex) -> String {
+        self.player_names.get(player as usize)
+            .cloned()
+            .unwrap_or_else(|| format!("@{}", player as usize + 1))
+    }
+    fn player_list(&self, players: &[PlayerIndex]) -> String {
+        if players.is_empty() {
+            self.translate("nobody", &[])
+        } else {
+            players.iter().map(|player| self.player(*player)).collect::<Vec<_>>().join(", ")
+        }
+    }
+    fn player_references(&self, players: &[PlayerReference]) -> String {
+        self.player_list(&players.iter().map(PlayerReference::index).collect::<Vec<_>>())
+    }
+    /// Replaces mentions like `@3` with the player's name.
+    /// The text is read once, so a name containing `@1` isn't replaced again.
+    /// The longest number that is a player wins, so `@12` isn't read as `@1` followed by a 2.
+    fn replace_mentions(&self, text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(at) = rest.find('@') {
+            out.push_str(&rest[..at]);
+            rest = &rest[at + 1..];
+
+            let digits = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+            let mention = (1..=digits).rev().find_map(|length| {
+                let name = rest[..length].parse::<usize>().ok()
+                    .and_then(|number| number.checked_sub(1))
+                    .and_then(|index| self.player_names.get(index))?;
+                Some((length, name))
+            });
+            match mention {
+                Some((length, name)) => {
+                    out.push_str(name);
+                    rest = &rest[length..];
+                },
+                None => out.push('@'),
+            }
+        }
+        out.push_str(rest);
+        out
+    }
+
+    fn translate(&self, key: &str, values: &[String]) -> String {
+        self.lang.translate(key, values)
+    }
+}
@@ -6,6 +6,7 @@ pub mod packet;
 pub mod client_connection;
 pub mod vec_map;
 pub mod vec_set;
+pub mod lang;
 
 pub mod log {
     #[macro_export]
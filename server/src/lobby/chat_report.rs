@@ -0,0 +1,50 @@
+use serde::Serialize;
+
+use crate::{game::{chat::{ChatMessage, ChatMessageVariant, MessageSender}, player::{PlayerIndex, PlayerReference}, Game}, strings::TidyableString};
+
+/// A player's report of another player's chat, kept in the lobby's moderation log for review
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatReport{
+    /// Which of the lobby's games this is from, since the next game reuses player indices
+    pub game: u32,
+    pub reporter: PlayerIndex,
+    pub reporter_name: String,
+    pub reported: PlayerIndex,
+    pub reported_name: String,
+    pub reason: String,
+    /// The reported player's most recent messages that the reporter could see
+    pub messages: Vec<ChatMessage>,
+}
+impl ChatReport{
+    pub const MAX_REASON_LENGTH: usize = 200;
+    pub const MAX_MESSAGES: usize = 50;
+    /// Reports past this in one game are dropped, so one player can't fill up the server's memory
+    pub const MAX_REPORTS_PER_REPORTER: usize = 20;
+
+    pub fn new(game: &Game, game_number: u32, reporter: PlayerReference, reported: PlayerReference, reason: String)->Self{
+        let sent_messages: Vec<ChatMessage> = reporter.chat_messages(game).iter()
+            .filter(|message| Self::sent_by(message, reported.index()))
+            .cloned()
+            .collect();
+
+        Self {
+            game: game_number,
+            reporter: reporter.index(),
+            reporter_name: reporter.name(game).clone(),
+            reported: reported.index(),
+            reported_name: reported.name(game).clone(),
+            reason: reason.trim_newline().trim_whitespace().truncate(Self::MAX_REASON_LENGTH),
+            messages: sent_messages[sent_messages.len().saturating_sub(Self::MAX_MESSAGES)..].to_vec(),
+        }
+    }
+
+    fn sent_by(message: &ChatMessage, player: PlayerIndex)->bool{
+        match &message.variant {
+            ChatMessageVariant::Normal { message_sender: MessageSender::Player { player: sender }, .. } |
+            ChatMessageVariant::Normal { message_sender: MessageSender::LivingToDead { player: sender }, .. } |
+            ChatMessageVariant::Whisper { from_player_index: sender, .. } => *sender == player,
+            _ => false
+        }
+    }
+}
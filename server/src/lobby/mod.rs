@@ -2,6 +2,9 @@
 pub mod lobby_client;
 pub mod game_client;
 pub mod on_client_message;
+pub mod presets;
+pub mod settings_code;
+pub mod chat_report;
 mod name_validation;
 
 use std::time::Duration;
@@ -10,7 +13,7 @@ use lobby_client::Ready;
 
 use crate::{
     client_connection::ClientConnection, game::{
-        player::PlayerReference, role_list::RoleOutline, settings::Settings, spectator::{spectator_pointer::{SpectatorIndex, SpectatorPointer}, SpectatorInitializeParameters}, Game
+        chat::ChatMessageID, player::PlayerReference, role_list::RoleOutline, role_list_analysis::RoleListAnalysis, settings::Settings, spectator::{spectator_pointer::{SpectatorID, SpectatorPointer}, SpectatorInitializeParameters}, Game
     }, listener::RoomCode, lobby::game_client::GameClientLocation, packet::{
         RejectJoinReason,
         ToClientPacket,
@@ -18,12 +21,20 @@ use crate::{
 };
 
 
-use self::{game_client::GameClient, lobby_client::{LobbyClient, LobbyClientID, LobbyClientType}};
+use self::{chat_report::ChatReport, game_client::GameClient, lobby_client::{LobbyClient, LobbyClientID, LobbyClientType}};
 
 pub struct Lobby {
     room_code: RoomCode,
     pub name: String,
     lobby_state: LobbyState,
+    /// Chat reports from this lobby's games, oldest first
+    moderation_log: Vec<ChatReport>,
+    /// How many games this lobby has started, so reports say which game they're from
+    games_started: u32,
+    /// Lobby chat isn't part of a game, so it counts its own message IDs
+    next_chat_message_id: ChatMessageID,
+    /// The last role list analysis and the settings it was made for, since analyzing is slow
+    role_list_analysis: Option<(String, RoleListAnalysis)>,
 }
 
 enum LobbyState {
@@ -51,7 +62,11 @@ impl Lobby {
             lobby_state: LobbyState::Lobby{
                 settings: Settings::default(),
                 clients: VecMap::new()
-            }
+            },
+            moderation_log: Vec::new(),
+            games_started: 0,
+            next_chat_message_id: 0,
+            role_list_analysis: None,
         }
     }
 
@@ -136,13 +151,13 @@ impl Lobby {
 
                 send.send(ToClientPacket::AcceptJoin{room_code: self.room_code, in_game: true, player_id: lobby_client_id, spectator: true});
 
-                let new_index: SpectatorIndex = game.add_spectator(SpectatorInitializeParameters {
+                let new_id: SpectatorID = game.add_spectator(SpectatorInitializeParameters {
                     connection: ClientConnection::Connected(send.clone()),
                     host: is_host,
                 });
 
 
-                let new_client = GameClient::new_spectator(new_index, is_host);
+                let new_client = GameClient::new_spectator(new_id, is_host);
 
 
 
@@ -191,8 +206,8 @@ impl Lobby {
                             player_ref.quit(game);
                         }
                     },
-                    GameClientLocation::Spectator(idx) => {
-                        game.remove_spectator(idx);
+                    GameClientLocation::Spectator(id) => {
+                        game.remove_spectator(id);
                     }
                 }
             },
@@ -228,11 +243,16 @@ impl Lobby {
             LobbyState::Game {game, clients: players} => {
                 let Some(game_player) = players.get_mut(&id) else {return};
 
-                if let GameClientLocation::Player(player_index) = game_player.client_location {
-                    if let Ok(player_ref) = PlayerReference::new(game, player_index) {
-                        if !player_ref.is_disconnected(game) {
-                            player_ref.lose_connection(game);
+                match game_player.client_location {
+                    GameClientLocation::Player(player_index) => {
+                        if let Ok(player_ref) = PlayerReference::new(game, player_index) {
+                            if !player_ref.is_disconnected(game) {
+                                player_ref.lose_connection(game);
+                            }
                         }
+                    },
+                    GameClientLocation::Spectator(id) => {
+                        SpectatorPointer::new(id).lose_connection(game);
                     }
                 }
                 
@@ -286,6 +306,17 @@ impl Lobby {
                             .collect()
                     });
 
+                    Ok(())
+                }else if let GameClientLocation::Spectator(id) = game_player.client_location {
+                    let spectator_pointer = SpectatorPointer::new(id);
+                    if !spectator_pointer.could_reconnect(game) {
+                        send.send(ToClientPacket::RejectJoin{reason: RejectJoinReason::PlayerDoesntExist});
+                        return Err(RejectJoinReason::PlayerDoesntExist)
+                    };
+
+                    send.send(ToClientPacket::AcceptJoin{room_code: self.room_code, in_game: true, player_id: lobby_client_id, spectator: true});
+                    spectator_pointer.connect(game, send.clone());
+
                     Ok(())
                 }else{
                     send.send(ToClientPacket::RejectJoin{reason: RejectJoinReason::PlayerDoesntExist});
@@ -389,7 +420,17 @@ impl Lobby {
         client.send(ToClientPacket::PhaseTimes { phase_time_settings: settings.phase_times.clone() });
         client.send(ToClientPacket::RoleList { role_list: settings.role_list.clone() });
         client.send(ToClientPacket::EnabledRoles { roles: settings.enabled_roles.clone().into_iter().collect() });
-        client.send(ToClientPacket::EnabledModifiers { modifiers: settings.enabled_modifiers.clone().into_iter().collect() });
+        client.send(ToClientPacket::new_enabled_modifiers(settings));
+        client.send(ToClientPacket::new_modifier_parameters(settings));
+        client.send(ToClientPacket::RoleListConstraints { constraints: settings.role_list_constraints.clone() });
+        client.send(ToClientPacket::CustomRoleSets { custom_role_sets: settings.custom_role_sets.clone() });
+        client.send(ToClientPacket::CustomInsiderGroups { custom_insider_groups: settings.custom_insider_groups.clone() });
+        client.send(ToClientPacket::CustomFactions { custom_factions: settings.custom_factions.clone() });
+        client.send(ToClientPacket::RoleConfigurations { role_configurations: settings.role_configurations.clone() });
+        client.send(ToClientPacket::NominationTieBreak { tie_break: settings.nomination_tie_break });
+        client.send(ToClientPacket::SpectatorSettings { spectator_settings: settings.spectator_settings.clone() });
+        client.send(ToClientPacket::WhisperSettings { whisper_settings: settings.whisper_settings.clone() });
+        client.send(ToClientPacket::ChatFilter { chat_filter: settings.chat_filter.clone() });
     }
 
     //send the list of players to all players while in the lobby
@@ -1,8 +1,8 @@
 use std::{collections::VecDeque, time::{Duration, Instant}};
 
-use crate::{game::{chat::{ChatMessage, ChatMessageVariant}, phase::PhaseType, player::{PlayerIndex, PlayerInitializeParameters}, spectator::{spectator_pointer::SpectatorIndex, SpectatorInitializeParameters}, Game}, lobby::game_client::{GameClient, GameClientLocation}, log, packet::{ToClientPacket, ToServerPacket}, strings::TidyableString, vec_map::VecMap, websocket_connections::connection::ClientSender};
+use crate::{game::{chat::{ChatMessage, ChatMessageVariant}, components::muted::Muted, modifiers::{ModifierParametersError, ModifierType}, phase::PhaseType, role_list::{RoleList, RoleListParseError, RoleOutline}, role_list_analysis::RoleListAnalysis, settings::SpectatorSettings, player::{PlayerIndex, PlayerInitializeParameters, PlayerReference}, spectator::{spectator_pointer::SpectatorID, SpectatorInitializeParameters}, Game}, lobby::game_client::{GameClient, GameClientLocation}, log, packet::{ToClientPacket, ToServerPacket}, strings::TidyableString, vec_map::VecMap, websocket_connections::connection::ClientSender};
 
-use super::{lobby_client::{LobbyClient, LobbyClientID, LobbyClientType, Ready}, name_validation::{self, sanitize_server_name}, Lobby, LobbyState};
+use super::{chat_report::ChatReport, lobby_client::{LobbyClient, LobbyClientID, LobbyClientType, Ready}, name_validation::{self, sanitize_server_name}, presets::PresetLibrary, settings_code, Lobby, LobbyState};
 
 pub const MESSAGE_PER_SECOND_LIMIT: u64 = 1;
 pub const MESSAGE_PER_SECOND_LIMIT_TIME: Duration = Duration::from_secs(10);
@@ -16,7 +16,16 @@ impl Lobby {
             ToServerPacket::Judgement { .. } |
             ToServerPacket::SendChatMessage { .. } |
             ToServerPacket::SendLobbyMessage { .. } |
-            ToServerPacket::SendWhisper { .. } => {
+            ToServerPacket::SendWhisper { .. } |
+            ToServerPacket::SaveWill { .. } |
+            ToServerPacket::SaveWillTemplate { .. } |
+            ToServerPacket::ReportPlayer { .. } |
+            ToServerPacket::GetModerationLog |
+            ToServerPacket::AnalyzeRoleList |
+            ToServerPacket::ListPresets { .. } |
+            ToServerPacket::LoadPreset { .. } |
+            ToServerPacket::SavePreset { .. } |
+            ToServerPacket::DeletePreset { .. } => {
 
                 let last_message_times = match &mut self.lobby_state {
                     LobbyState::Game { clients, .. } => {
@@ -63,13 +72,21 @@ impl Lobby {
 
         match incoming_packet {
             ToServerPacket::SendLobbyMessage { text } => {
-                let LobbyState::Lobby { clients, .. } = &mut self.lobby_state else {
+                let LobbyState::Lobby { clients, settings } = &mut self.lobby_state else {
                     log!(error "Lobby"; "{} {}", "ToServerPacket::SendLobbyMessage can not be used outside of LobbyState::Lobby", lobby_client_id);
                     return
                 };
 
                 let text = text.trim_newline().trim_whitespace().truncate(100);
                 if text.is_empty() {return}
+                let id = self.next_chat_message_id;
+                self.next_chat_message_id += 1;
+                let Some(text) = settings.chat_filter.apply(&text) else {
+                    send.send(ToClientPacket::AddChatMessages { chat_messages: vec![
+                        ChatMessage::new_private(id, ChatMessageVariant::BlockedByChatFilter)
+                    ]});
+                    return
+                };
                 
                 let name = if let Some(
                     LobbyClient { client_type: LobbyClientType::Player { name }, .. }
@@ -81,6 +98,7 @@ impl Lobby {
 
                 self.send_to_all(ToClientPacket::AddChatMessages { chat_messages: vec![
                     ChatMessage::new_non_private(
+                        id,
                         ChatMessageVariant::LobbyMessage { sender: name, text }, 
                         crate::game::chat::ChatGroup::All
                     )
@@ -180,7 +198,7 @@ impl Lobby {
                     if !player.is_host() {return}
                 }
 
-                settings.role_list.simplify();
+                settings.role_list.simplify(&settings.custom_role_sets);
                 let role_list = settings.role_list.clone();
                 
                 self.send_to_all(ToClientPacket::RoleList { role_list });
@@ -195,14 +213,14 @@ impl Lobby {
                 };
 
                 let mut next_player_index: PlayerIndex = 0;
-                let mut next_spectator_index: SpectatorIndex = 0;
+                let mut next_spectator_id: SpectatorID = 0;
 
                 for (lobby_client_id, lobby_client) in clients.clone().into_iter() {
                     
                     game_clients.insert(lobby_client_id, 
                         if let LobbyClientType::Spectator = lobby_client.client_type {
                             GameClient {
-                                client_location: GameClientLocation::Spectator(next_spectator_index),
+                                client_location: GameClientLocation::Spectator(next_spectator_id),
                                 host: lobby_client.is_host(),
                                 last_message_times: VecDeque::new(),
                             }
@@ -229,7 +247,7 @@ impl Lobby {
                                 host: lobby_client.is_host(),
                                 connection: lobby_client.connection,
                             });
-                            next_spectator_index += 1;
+                            next_spectator_id += 1;
                         }
                     }
                 }
@@ -244,6 +262,7 @@ impl Lobby {
                 };
                 
                 log!(info "Lobby"; "Game started with room code {}", self.room_code);
+                self.games_started = self.games_started.saturating_add(1);
 
                 self.lobby_state = LobbyState::Game{
                     game,
@@ -303,7 +322,13 @@ impl Lobby {
                     if !player.is_host() {return}
                 }
 
-                settings.role_list = role_list;
+                settings.role_list = match RoleList::from_json(&role_list) {
+                    Ok(role_list) => role_list,
+                    Err(error) => {
+                        send.send(ToClientPacket::RejectRoleList { error });
+                        return;
+                    }
+                };
                 Lobby::set_rolelist_length(settings, clients);
                 
                 let role_list = settings.role_list.clone();
@@ -320,6 +345,13 @@ impl Lobby {
                 }
 
                 if settings.role_list.0.len() <= index as usize {return}
+                let role_outline = match RoleOutline::from_json(&role_outline) {
+                    Ok(role_outline) => role_outline,
+                    Err(error) => {
+                        send.send(ToClientPacket::RejectRoleList { error: RoleListParseError { outline: Some(index), ..error } });
+                        return;
+                    }
+                };
                 let Some(unset_outline) = settings.role_list.0.get_mut(index as usize) else {return};
                 *unset_outline = role_outline.clone();
                 
@@ -334,11 +366,108 @@ impl Lobby {
                     if !player.is_host() {return}
                 }
 
-                settings.role_list.simplify();
+                settings.role_list.simplify(&settings.custom_role_sets);
                 let role_list = settings.role_list.clone();
                 
                 self.send_to_all(ToClientPacket::RoleList { role_list });
             }
+            ToServerPacket::SetRoleListConstraints { constraints } => {
+                let LobbyState::Lobby{ settings, clients } = &mut self.lobby_state else {
+                    log!(error "Lobby"; "{} {}", "Can't modify game settings outside of the lobby menu", lobby_client_id);
+                    return;
+                };
+                if let Some(player) = clients.get(&lobby_client_id){
+                    if !player.is_host() {return}
+                }
+
+                if let Err(reason) = settings.check_role_list_constraints(&constraints) {
+                    send.send(ToClientPacket::RejectRoleListConstraints { reason });
+                    return;
+                }
+
+                settings.role_list_constraints = constraints;
+                let constraints = settings.role_list_constraints.clone();
+                
+                self.send_to_all(ToClientPacket::RoleListConstraints { constraints });
+            }
+            ToServerPacket::SetCustomRoleSets { custom_role_sets } => {
+                let LobbyState::Lobby{ settings, clients } = &mut self.lobby_state else {
+                    log!(error "Lobby"; "{} {}", "Can't modify game settings outside of the lobby menu", lobby_client_id);
+                    return;
+                };
+                if let Some(player) = clients.get(&lobby_client_id){
+                    if !player.is_host() {return}
+                }
+
+                if let Err(reason) = settings.check_custom_role_sets(&custom_role_sets) {
+                    send.send(ToClientPacket::RejectCustomRoleSets { reason });
+                    return;
+                }
+
+                settings.custom_role_sets = custom_role_sets;
+                let custom_role_sets = settings.custom_role_sets.clone();
+
+                self.send_to_all(ToClientPacket::CustomRoleSets { custom_role_sets });
+            }
+            ToServerPacket::SetCustomInsiderGroups { custom_insider_groups } => {
+                let LobbyState::Lobby{ settings, clients } = &mut self.lobby_state else {
+                    log!(error "Lobby"; "{} {}", "Can't modify game settings outside of the lobby menu", lobby_client_id);
+                    return;
+                };
+                if let Some(player) = clients.get(&lobby_client_id){
+                    if !player.is_host() {return}
+                }
+
+                if let Err(reason) = settings.check_custom_insider_group_changes(&custom_insider_groups) {
+                    send.send(ToClientPacket::RejectCustomInsiderGroups { reason });
+                    return;
+                }
+
+                settings.custom_insider_groups = custom_insider_groups;
+                let custom_insider_groups = settings.custom_insider_groups.clone();
+
+                self.send_to_all(ToClientPacket::CustomInsiderGroups { custom_insider_groups });
+            }
+            ToServerPacket::SetCustomFactions { custom_factions } => {
+                let LobbyState::Lobby{ settings, clients } = &mut self.lobby_state else {
+                    log!(error "Lobby"; "{} {}", "Can't modify game settings outside of the lobby menu", lobby_client_id);
+                    return;
+                };
+                if let Some(player) = clients.get(&lobby_client_id){
+                    if !player.is_host() {return}
+                }
+
+                if let Err(reason) = settings.check_custom_faction_changes(&custom_factions) {
+                    send.send(ToClientPacket::RejectCustomFactions { reason });
+                    return;
+                }
+
+                settings.custom_factions = custom_factions;
+                let custom_factions = settings.custom_factions.clone();
+
+                self.send_to_all(ToClientPacket::CustomFactions { custom_factions });
+            }
+            ToServerPacket::AnalyzeRoleList => {
+                let LobbyState::Lobby{ settings, clients } = &self.lobby_state else {
+                    log!(error "Lobby"; "{} {}", "Can't analyze the role list outside of the lobby menu", lobby_client_id);
+                    return;
+                };
+                if let Some(player) = clients.get(&lobby_client_id){
+                    if !player.is_host() {return}
+                }
+
+                let Ok(settings_json) = serde_json::to_string(settings) else {return};
+                let analysis = match &self.role_list_analysis {
+                    Some((analyzed_settings, analysis)) if *analyzed_settings == settings_json => analysis.clone(),
+                    _ => {
+                        let analysis = RoleListAnalysis::new(settings);
+                        self.role_list_analysis = Some((settings_json, analysis.clone()));
+                        analysis
+                    }
+                };
+
+                send.send(ToClientPacket::RoleListAnalysis { analysis });
+            }
             ToServerPacket::SetEnabledRoles {roles } => {
                 let LobbyState::Lobby{ settings, .. } = &mut self.lobby_state else {
                     log!(error "Lobby"; "{} {}", "Can't modify game settings outside of the lobby menu", lobby_client_id);
@@ -347,8 +476,16 @@ impl Lobby {
 
 
                 settings.enabled_roles = roles.into_iter().collect();
+                let old_custom_role_sets = settings.custom_role_sets.clone();
+                settings.remove_disabled_roles_from_custom_role_sets();
+                let custom_role_sets = (settings.custom_role_sets != old_custom_role_sets)
+                    .then(|| settings.custom_role_sets.clone());
+
                 let roles = settings.enabled_roles.clone().into_iter().collect();
                 self.send_to_all(ToClientPacket::EnabledRoles { roles });
+                if let Some(custom_role_sets) = custom_role_sets {
+                    self.send_to_all(ToClientPacket::CustomRoleSets { custom_role_sets });
+                }
             }
             ToServerPacket::SetEnabledModifiers {modifiers } => {
                 let LobbyState::Lobby{ settings, .. } = &mut self.lobby_state else {
@@ -356,9 +493,250 @@ impl Lobby {
                     return;
                 };
 
-                settings.enabled_modifiers = modifiers.into_iter().collect();
-                let modifiers = settings.enabled_modifiers.clone().into_iter().collect();
-                self.send_to_all(ToClientPacket::EnabledModifiers { modifiers });
+                // Modifiers that stay enabled keep their parameters
+                settings.enabled_modifiers = VecMap::new_from_vec(
+                    modifiers.into_iter().map(|modifier| {
+                        let state = settings.enabled_modifiers.get(&modifier).cloned()
+                            .unwrap_or_else(|| modifier.default_state());
+                        (modifier, state)
+                    }).collect()
+                );
+                let enabled_modifiers = ToClientPacket::new_enabled_modifiers(settings);
+                let modifier_parameters = ToClientPacket::new_modifier_parameters(settings);
+                self.send_to_all(enabled_modifiers);
+                self.send_to_all(modifier_parameters);
+            }
+            ToServerPacket::SetModifierParameters { modifier: state } => {
+                let LobbyState::Lobby{ settings, clients } = &mut self.lobby_state else {
+                    log!(error "Lobby"; "{} {}", "Can't modify game settings outside of the lobby menu", lobby_client_id);
+                    return;
+                };
+                if let Some(player) = clients.get(&lobby_client_id){
+                    if !player.is_host() {return}
+                }
+
+                let modifier = ModifierType::from(&state);
+                if !settings.enabled_modifiers.contains(&modifier) {
+                    send.send(ToClientPacket::RejectModifierParameters { reason: ModifierParametersError::NotEnabled { modifier } });
+                    return;
+                }
+                if !state.has_valid_parameters() {
+                    send.send(ToClientPacket::RejectModifierParameters { reason: ModifierParametersError::InvalidParameters { modifier } });
+                    return;
+                }
+
+                settings.enabled_modifiers.insert(modifier, state);
+                let modifier_parameters = ToClientPacket::new_modifier_parameters(settings);
+                self.send_to_all(modifier_parameters);
+            }
+            ToServerPacket::SetRoleConfigurations { role_configurations } => {
+                let LobbyState::Lobby{ settings, clients } = &mut self.lobby_state else {
+                    log!(error "Lobby"; "{} {}", "Can't modify game settings outside of the lobby menu", lobby_client_id);
+                    return;
+                };
+                if let Some(player) = clients.get(&lobby_client_id){
+                    if !player.is_host() {return}
+                }
+
+                if let Some((role, _)) = role_configurations.iter().find(|(role, configuration)| !configuration.is_valid_for(**role)) {
+                    send.send(ToClientPacket::RejectRoleConfigurations { role: *role });
+                    return;
+                }
+
+                settings.role_configurations = VecMap::new_from_vec(role_configurations.into_iter().collect());
+                let role_configurations = settings.role_configurations.clone();
+                self.send_to_all(ToClientPacket::RoleConfigurations { role_configurations });
+            }
+            ToServerPacket::SetNominationTieBreak { tie_break } => {
+                let LobbyState::Lobby{ settings, clients } = &mut self.lobby_state else {
+                    log!(error "Lobby"; "{} {}", "Can't modify game settings outside of the lobby menu", lobby_client_id);
+                    return;
+                };
+                if let Some(player) = clients.get(&lobby_client_id){
+                    if !player.is_host() {return}
+                }
+
+                settings.nomination_tie_break = tie_break;
+                self.send_to_all(ToClientPacket::NominationTieBreak { tie_break });
+            }
+            ToServerPacket::SetSpectatorSettings { spectator_settings } => {
+                let LobbyState::Lobby{ settings, clients } = &mut self.lobby_state else {
+                    log!(error "Lobby"; "{} {}", "Can't modify game settings outside of the lobby menu", lobby_client_id);
+                    return;
+                };
+                if let Some(player) = clients.get(&lobby_client_id){
+                    if !player.is_host() {return}
+                }
+
+                settings.spectator_settings = SpectatorSettings {
+                    stream_delay_seconds: spectator_settings.stream_delay_seconds.min(SpectatorSettings::MAX_STREAM_DELAY_SECONDS),
+                    ..spectator_settings
+                };
+                let spectator_settings = settings.spectator_settings.clone();
+                self.send_to_all(ToClientPacket::SpectatorSettings { spectator_settings });
+            }
+            ToServerPacket::SetWhisperSettings { whisper_settings } => {
+                let LobbyState::Lobby{ settings, clients } = &mut self.lobby_state else {
+                    log!(error "Lobby"; "{} {}", "Can't modify game settings outside of the lobby menu", lobby_client_id);
+                    return;
+                };
+                if let Some(player) = clients.get(&lobby_client_id){
+                    if !player.is_host() {return}
+                }
+
+                settings.whisper_settings = whisper_settings.clamped();
+                let whisper_settings = settings.whisper_settings.clone();
+                self.send_to_all(ToClientPacket::WhisperSettings { whisper_settings });
+            }
+            ToServerPacket::SetChatFilter { chat_filter } => {
+                let LobbyState::Lobby{ settings, clients } = &mut self.lobby_state else {
+                    log!(error "Lobby"; "{} {}", "Can't modify game settings outside of the lobby menu", lobby_client_id);
+                    return;
+                };
+                if let Some(player) = clients.get(&lobby_client_id){
+                    if !player.is_host() {return}
+                }
+
+                if let Err(reason) = chat_filter.check() {
+                    send.send(ToClientPacket::RejectChatFilter { reason });
+                    return;
+                }
+                settings.chat_filter = chat_filter.clone();
+                self.send_to_all(ToClientPacket::ChatFilter { chat_filter });
+            }
+            ToServerPacket::DeleteChatMessage { id } => {
+                let LobbyState::Game { game, clients } = &mut self.lobby_state else {
+                    log!(error "Lobby"; "{} {}", "ToServerPacket::DeleteChatMessage can not be used outside of LobbyState::Game", lobby_client_id);
+                    return;
+                };
+                if !clients.get(&lobby_client_id).is_some_and(|client| client.host) {return}
+
+                game.delete_chat_message(id);
+            }
+            ToServerPacket::SetPlayerMuted { player_index, muted } => {
+                let LobbyState::Game { game, clients } = &mut self.lobby_state else {
+                    log!(error "Lobby"; "{} {}", "ToServerPacket::SetPlayerMuted can not be used outside of LobbyState::Game", lobby_client_id);
+                    return;
+                };
+                if !clients.get(&lobby_client_id).is_some_and(|client| client.host) {return}
+
+                if let Ok(player) = PlayerReference::new(game, player_index) {
+                    Muted::set_muted(game, player, muted);
+                }
+            }
+            ToServerPacket::ReportPlayer { player_index, reason } => {
+                let LobbyState::Game { game, clients } = &self.lobby_state else {
+                    log!(error "Lobby"; "{} {}", "ToServerPacket::ReportPlayer can not be used outside of LobbyState::Game", lobby_client_id);
+                    return;
+                };
+                let Some(GameClient { client_location: GameClientLocation::Player(reporter), .. }) = clients.get(&lobby_client_id) else {return};
+                let (Ok(reporter), Ok(reported)) = (PlayerReference::new(game, *reporter), PlayerReference::new(game, player_index)) else {return};
+                if reporter == reported {return}
+                let reports_this_game = self.moderation_log.iter()
+                    .filter(|report| report.game == self.games_started && report.reporter == reporter.index())
+                    .count();
+                if reports_this_game >= ChatReport::MAX_REPORTS_PER_REPORTER {return}
+
+                let report = ChatReport::new(game, self.games_started, reporter, reported, reason);
+                log!(info "Report"; "{} reported {} in game {} of lobby {}: {}", report.reporter_name, report.reported_name, report.game, self.room_code, report.reason);
+                self.moderation_log.push(report);
+            }
+            ToServerPacket::GetModerationLog => {
+                let is_host = match &self.lobby_state {
+                    LobbyState::Lobby { clients, .. } => clients.get(&lobby_client_id).is_some_and(|client| client.is_host()),
+                    LobbyState::Game { clients, .. } => clients.get(&lobby_client_id).is_some_and(|client| client.host),
+                    LobbyState::Closed => false
+                };
+                if !is_host {return}
+
+                send.send(ToClientPacket::ModerationLog { reports: self.moderation_log.clone() });
+            }
+            ToServerPacket::ListPresets { owner_key } => {
+                let LobbyState::Lobby{ clients, .. } = &self.lobby_state else {
+                    log!(error "Lobby"; "{} {}", "Can't list presets outside of the lobby menu", lobby_client_id);
+                    return;
+                };
+                if let Some(player) = clients.get(&lobby_client_id){
+                    if !player.is_host() {return}
+                }
+
+                Self::send_presets(send, &owner_key);
+            }
+            ToServerPacket::LoadPreset { owner_key, name } => {
+                let LobbyState::Lobby{ settings, clients } = &mut self.lobby_state else {
+                    log!(error "Lobby"; "{} {}", "Can't modify game settings outside of the lobby menu", lobby_client_id);
+                    return;
+                };
+                if let Some(player) = clients.get(&lobby_client_id){
+                    if !player.is_host() {return}
+                }
+
+                match PresetLibrary::default().load(&owner_key, &name) {
+                    Ok(preset_settings) => {
+                        *settings = preset_settings;
+                        Self::set_rolelist_length(settings, clients);
+                        for client in clients.values() {
+                            Self::send_settings(client, settings, self.name.clone());
+                        }
+                    },
+                    Err(reason) => send.send(ToClientPacket::RejectPreset { reason })
+                }
+            }
+            ToServerPacket::SavePreset { owner_key, name } => {
+                let LobbyState::Lobby{ settings, clients } = &self.lobby_state else {
+                    log!(error "Lobby"; "{} {}", "Can't save game settings outside of the lobby menu", lobby_client_id);
+                    return;
+                };
+                if let Some(player) = clients.get(&lobby_client_id){
+                    if !player.is_host() {return}
+                }
+
+                match PresetLibrary::default().save(&owner_key, &name, settings) {
+                    Ok(()) => Self::send_presets(send, &owner_key),
+                    Err(reason) => send.send(ToClientPacket::RejectPreset { reason })
+                }
+            }
+            ToServerPacket::DeletePreset { owner_key, name } => {
+                let LobbyState::Lobby{ clients, .. } = &self.lobby_state else {
+                    log!(error "Lobby"; "{} {}", "Can't delete presets outside of the lobby menu", lobby_client_id);
+                    return;
+                };
+                if let Some(player) = clients.get(&lobby_client_id){
+                    if !player.is_host() {return}
+                }
+
+                match PresetLibrary::default().delete(&owner_key, &name) {
+                    Ok(()) => Self::send_presets(send, &owner_key),
+                    Err(reason) => send.send(ToClientPacket::RejectPreset { reason })
+                }
+            }
+            ToServerPacket::ExportSettings => {
+                let LobbyState::Lobby{ settings, .. } = &self.lobby_state else {
+                    log!(error "Lobby"; "{} {}", "Can't export game settings outside of the lobby menu", lobby_client_id);
+                    return;
+                };
+
+                send.send(ToClientPacket::SettingsCode { code: settings_code::encode_settings(settings) });
+            }
+            ToServerPacket::ImportSettings { code } => {
+                let LobbyState::Lobby{ settings, clients } = &mut self.lobby_state else {
+                    log!(error "Lobby"; "{} {}", "Can't modify game settings outside of the lobby menu", lobby_client_id);
+                    return;
+                };
+                if let Some(player) = clients.get(&lobby_client_id){
+                    if !player.is_host() {return}
+                }
+
+                match settings_code::decode_settings(&code) {
+                    Ok(imported_settings) => {
+                        *settings = imported_settings;
+                        Self::set_rolelist_length(settings, clients);
+                        for client in clients.values() {
+                            Self::send_settings(client, settings, self.name.clone());
+                        }
+                    },
+                    Err(reason) => send.send(ToClientPacket::RejectSettingsCode { reason })
+                }
             }
             ToServerPacket::Leave => {
                 self.remove_player(lobby_client_id);
@@ -407,8 +785,8 @@ impl Lobby {
                         GameClientLocation::Player(player_index) => {
                             game.on_client_message(player_index, incoming_packet)
                         }
-                        GameClientLocation::Spectator(spectator_index) => {
-                            game.on_spectator_message(spectator_index, incoming_packet)
+                        GameClientLocation::Spectator(spectator_id) => {
+                            game.on_spectator_message(spectator_id, incoming_packet)
                         }
                     }
                 }
@@ -416,4 +794,11 @@ impl Lobby {
             }
         }
     }
+
+    fn send_presets(send: &ClientSender, owner_key: &str) {
+        match PresetLibrary::default().list(owner_key) {
+            Ok(presets) => send.send(ToClientPacket::Presets { presets }),
+            Err(reason) => send.send(ToClientPacket::RejectPreset { reason })
+        }
+    }
 }
\ No newline at end of file
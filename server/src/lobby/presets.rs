@@ -0,0 +1,215 @@
+use std::{fs, io, path::{Path, PathBuf}};
+
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+
+use crate::{game::settings::Settings, log};
+
+/// Bump this when the saved format changes, and convert older files in `PresetFile::from_json`
+pub const PRESET_FORMAT_VERSION: u8 = 1;
+pub const SAVED_PRESETS_DIRECTORY: &str = "./saved_presets";
+
+const MAX_PRESET_NAME_LENGTH: usize = 30;
+const MAX_PRESETS_PER_OWNER: usize = 50;
+/// Together with the preset limit, this caps how much disk space one owner can use
+const MAX_PRESET_FILE_SIZE: usize = 64 * 1024;
+const MIN_OWNER_KEY_LENGTH: usize = 8;
+const MAX_OWNER_KEY_LENGTH: usize = 64;
+
+lazy_static!(
+    static ref BUILT_IN_PRESETS: Vec<PresetFile> = [
+        include_str!("../../resources/presets/classic_8.json"),
+        include_str!("../../resources/presets/classic_15.json"),
+        include_str!("../../resources/presets/beginner_12.json"),
+        include_str!("../../resources/presets/cult_10.json"),
+    ].into_iter()
+        .map(|json| PresetFile::from_json(json).expect("built in presets should be valid"))
+        .collect();
+);
+
+/// How a preset is stored on disk
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PresetFile {
+    pub version: u8,
+    pub name: String,
+    pub settings: Settings,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PresetSummary {
+    pub name: String,
+    pub built_in: bool,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum PresetError {
+    InvalidName,
+    InvalidOwnerKey,
+    NotFound,
+    /// Built in presets can't be overwritten or deleted
+    BuiltIn,
+    TooManyPresets,
+    TooLarge,
+    /// The file was saved by a newer server
+    UnsupportedVersion,
+    InvalidSettings,
+    StorageFailed,
+}
+
+impl PresetFile {
+    fn new(name: String, settings: Settings) -> Self {
+        Self { version: PRESET_FORMAT_VERSION, name, settings }
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, PresetError> {
+        let value: serde_json::Value = serde_json::from_str(json).map_err(|_| PresetError::InvalidSettings)?;
+
+        match value.get("version").and_then(serde_json::Value::as_u64) {
+            Some(version) if version == PRESET_FORMAT_VERSION as u64 => {},
+            _ => return Err(PresetError::UnsupportedVersion)
+        }
+
+        let preset: PresetFile = serde_json::from_value(value).map_err(|_| PresetError::InvalidSettings)?;
+        if !preset.settings.has_valid_parameters() {
+            return Err(PresetError::InvalidSettings);
+        }
+        Ok(preset)
+    }
+}
+
+/// Named settings that hosts can load into their lobby.
+/// Built in presets ship with the server, and every host also gets their own presets saved as JSON files.
+/// Hosts are told apart by an owner key that the client keeps between sessions,
+/// since lobby client IDs change every time someone joins.
+pub struct PresetLibrary {
+    directory: PathBuf,
+}
+
+impl Default for PresetLibrary {
+    fn default() -> Self {
+        Self::new(SAVED_PRESETS_DIRECTORY)
+    }
+}
+
+impl PresetLibrary {
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self { directory: directory.into() }
+    }
+
+    pub fn list(&self, owner_key: &str) -> Result<Vec<PresetSummary>, PresetError> {
+        let mut presets: Vec<PresetSummary> = BUILT_IN_PRESETS.iter()
+            .map(|preset| PresetSummary { name: preset.name.clone(), built_in: true })
+            .collect();
+
+        let entries = match fs::read_dir(self.owner_directory(owner_key)?) {
+            Ok(entries) => entries,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(presets),
+            Err(error) => return Err(Self::storage_failed(error))
+        };
+
+        // Names come from the file names, so everything listed can be loaded and deleted by that name
+        let mut saved: Vec<PresetSummary> = entries
+            .filter_map(Result::ok)
+            .filter_map(|entry| {
+                let name = entry.path().file_stem()?.to_str()?.to_string();
+                if self.preset_path(owner_key, &name).ok()? != entry.path() {return None}
+                Self::read_preset(&entry.path()).ok()?;
+                Some(PresetSummary { name, built_in: false })
+            })
+            .collect();
+        saved.sort_by(|a, b| a.name.cmp(&b.name));
+
+        presets.append(&mut saved);
+        Ok(presets)
+    }
+
+    pub fn load(&self, owner_key: &str, name: &str) -> Result<Settings, PresetError> {
+        if let Some(preset) = Self::built_in(name) {
+            return Ok(preset.settings.clone());
+        }
+
+        Ok(Self::read_preset(&self.preset_path(owner_key, name)?)?.settings)
+    }
+
+    pub fn save(&self, owner_key: &str, name: &str, settings: &Settings) -> Result<(), PresetError> {
+        if Self::built_in(name).is_some() {
+            return Err(PresetError::BuiltIn);
+        }
+        let path = self.preset_path(owner_key, name)?;
+
+        if !path.exists() && self.list(owner_key)?.iter().filter(|preset| !preset.built_in).count() >= MAX_PRESETS_PER_OWNER {
+            return Err(PresetError::TooManyPresets);
+        }
+
+        let json = serde_json::to_string_pretty(&PresetFile::new(name.to_string(), settings.clone()))
+            .map_err(|_| PresetError::InvalidSettings)?;
+        if json.len() > MAX_PRESET_FILE_SIZE {
+            return Err(PresetError::TooLarge);
+        }
+
+        fs::create_dir_all(self.owner_directory(owner_key)?).map_err(Self::storage_failed)?;
+        fs::write(path, json).map_err(Self::storage_failed)
+    }
+
+    pub fn delete(&self, owner_key: &str, name: &str) -> Result<(), PresetError> {
+        if Self::built_in(name).is_some() {
+            return Err(PresetError::BuiltIn);
+        }
+
+        match fs::remove_file(self.preset_path(owner_key, name)?) {
+            Ok(()) => Ok(()),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Err(PresetError::NotFound),
+            Err(error) => Err(Self::storage_failed(error))
+        }
+    }
+
+    fn read_preset(path: &Path) -> Result<PresetFile, PresetError> {
+        let read = fs::metadata(path).and_then(|metadata| {
+            if metadata.len() > MAX_PRESET_FILE_SIZE as u64 {
+                Ok(None)
+            } else {
+                fs::read_to_string(path).map(Some)
+            }
+        });
+        match read {
+            Ok(Some(json)) => PresetFile::from_json(&json),
+            Ok(None) => Err(PresetError::TooLarge),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Err(PresetError::NotFound),
+            Err(error) => Err(Self::storage_failed(error))
+        }
+    }
+
+    fn built_in(name: &str) -> Option<&'static PresetFile> {
+        BUILT_IN_PRESETS.iter().find(|preset| preset.name == name)
+    }
+
+    /// Owner keys and names become part of the path, so they can't contain anything that would leave the directory
+    fn owner_directory(&self, owner_key: &str) -> Result<PathBuf, PresetError> {
+        if
+            !(MIN_OWNER_KEY_LENGTH..=MAX_OWNER_KEY_LENGTH).contains(&owner_key.len()) ||
+            !owner_key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(PresetError::InvalidOwnerKey);
+        }
+        Ok(self.directory.join(owner_key))
+    }
+
+    fn preset_path(&self, owner_key: &str, name: &str) -> Result<PathBuf, PresetError> {
+        if
+            name.trim().is_empty() || name.trim() != name ||
+            name.chars().count() > MAX_PRESET_NAME_LENGTH ||
+            !name.chars().all(|c| c.is_alphanumeric() || matches!(c, ' ' | '-' | '_' | '(' | ')'))
+        {
+            return Err(PresetError::InvalidName);
+        }
+        Ok(self.owner_directory(owner_key)?.join(format!("{name}.json")))
+    }
+
+    fn storage_failed(error: io::Error) -> PresetError {
+        log!(error "PresetLibrary"; "{}", error);
+        PresetError::StorageFailed
+    }
+}
@@ -0,0 +1,166 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use miniz_oxide::{deflate::compress_to_vec, inflate::decompress_to_vec_with_limit};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+use crate::game::{
+    components::insider_group::InsiderGroupID, game_conclusion::GameConclusion,
+    modifiers::ModifierType, role::Role, role_list::RoleSet, settings::Settings
+};
+
+/// Bump this when the encoded format changes, and decode older codes in `decode_settings`
+pub const SETTINGS_CODE_VERSION: u8 = 2;
+
+const COMPRESSION_LEVEL: u8 = 9;
+/// Stops a small code from decompressing into something huge
+const MAX_SETTINGS_JSON_SIZE: usize = 1024 * 1024;
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum SettingsCodeError {
+    /// Not a settings code at all
+    InvalidCode,
+    /// Made by a newer server
+    UnsupportedVersion,
+    /// Names that this server doesn't know, probably from a server with different roles or modifiers
+    UnknownEntries{entries: Vec<UnknownEntry>},
+    InvalidSettings,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct UnknownEntry {
+    /// Where the entry is, for example `roleList[3][0].role`
+    pub path: String,
+    pub kind: UnknownEntryKind,
+    pub value: String,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum UnknownEntryKind {
+    Role,
+    RoleSet,
+    Modifier,
+    GameConclusion,
+    InsiderGroup,
+}
+
+/// Turns settings into a string that can be pasted into a URL.
+/// The code is the version, a dot, then the deflated settings JSON in URL-safe base64.
+/// Settings that are the same as the default are left out, since missing settings decode as the default.
+pub fn encode_settings(settings: &Settings) -> String {
+    let mut value = serde_json::to_value(settings).expect("settings should always serialize");
+    let default = serde_json::to_value(Settings::default()).expect("settings should always serialize");
+    if let (Value::Object(fields), Value::Object(default_fields)) = (&mut value, &default) {
+        fields.retain(|key, field| default_fields.get(key) != Some(field));
+    }
+
+    let json = value.to_string();
+    let compressed = compress_to_vec(json.as_bytes(), COMPRESSION_LEVEL);
+    format!("{}.{}", SETTINGS_CODE_VERSION, URL_SAFE_NO_PAD.encode(compressed))
+}
+
+pub fn decode_settings(code: &str) -> Result<Settings, SettingsCodeError> {
+    let (version, data) = code.trim().split_once('.').ok_or(SettingsCodeError::InvalidCode)?;
+    let version: u8 = version.parse().map_err(|_| SettingsCodeError::InvalidCode)?;
+    let data = URL_SAFE_NO_PAD.decode(data).map_err(|_| SettingsCodeError::InvalidCode)?;
+
+    let json = match version {
+        // Version 1 codes weren't compressed
+        1 => data,
+        SETTINGS_CODE_VERSION => decompress_to_vec_with_limit(&data, MAX_SETTINGS_JSON_SIZE)
+            .map_err(|_| SettingsCodeError::InvalidCode)?,
+        _ => return Err(SettingsCodeError::UnsupportedVersion)
+    };
+
+    settings_from_json(&json)
+}
+
+/// Reads settings JSON the same way as a settings code, reporting anything this server doesn't know
+pub fn settings_from_json(json: &[u8]) -> Result<Settings, SettingsCodeError> {
+    let value: Value = serde_json::from_slice(json).map_err(|_| SettingsCodeError::InvalidCode)?;
+
+    let entries = find_unknown_entries(&value);
+    if !entries.is_empty() {
+        return Err(SettingsCodeError::UnknownEntries { entries });
+    }
+
+    let settings: Settings = serde_json::from_value(value).map_err(|_| SettingsCodeError::InvalidSettings)?;
+    if !settings.has_valid_parameters() {
+        return Err(SettingsCodeError::InvalidSettings);
+    }
+    Ok(settings)
+}
+
+/// Checks every name in the settings JSON before deserializing it,
+/// since some settings skip names they don't recognize instead of failing.
+fn find_unknown_entries(settings: &Value) -> Vec<UnknownEntry> {
+    let mut entries = Vec::new();
+
+    for (index, role) in array_at(settings, "enabledRoles") {
+        check::<Role>(&mut entries, format!("enabledRoles[{index}]"), UnknownEntryKind::Role, role);
+    }
+    for (index, configuration) in array_at(settings, "roleConfigurations") {
+        if let Some(role) = configuration.get(0) {
+            check::<Role>(&mut entries, format!("roleConfigurations[{index}][0]"), UnknownEntryKind::Role, role);
+        }
+    }
+    for (set_index, custom_role_set) in array_at(settings, "customRoleSets") {
+        for (index, role) in array_at(custom_role_set, "roles") {
+            check::<Role>(&mut entries, format!("customRoleSets[{set_index}].roles[{index}]"), UnknownEntryKind::Role, role);
+        }
+    }
+    for (faction_index, custom_faction) in array_at(settings, "customFactions") {
+        for (index, role) in array_at(custom_faction, "keepsGameRunning") {
+            check::<Role>(&mut entries, format!("customFactions[{faction_index}].keepsGameRunning[{index}]"), UnknownEntryKind::Role, role);
+        }
+    }
+    for (index, modifier) in array_at(settings, "enabledModifiers") {
+        if let Some(modifier_type) = modifier.get("type") {
+            check::<ModifierType>(&mut entries, format!("enabledModifiers[{index}].type"), UnknownEntryKind::Modifier, modifier_type);
+        }
+    }
+    for (index, constraint) in array_at(settings, "roleListConstraints") {
+        if let Some(roles) = constraint.get("roles") {
+            check_option(&mut entries, format!("roleListConstraints[{index}].roles"), roles);
+        }
+    }
+    for (outline_index, outline) in array_at(settings, "roleList") {
+        for (option_index, option) in outline.as_array().into_iter().flatten().enumerate() {
+            check_option(&mut entries, format!("roleList[{outline_index}][{option_index}]"), option);
+        }
+    }
+
+    entries
+}
+
+/// Checks a role outline option, or anything else shaped like one
+fn check_option(entries: &mut Vec<UnknownEntry>, path: String, option: &Value) {
+    if let Some(role_set) = option.get("roleSet") {
+        check::<RoleSet>(entries, format!("{path}.roleSet"), UnknownEntryKind::RoleSet, role_set);
+    }
+    if let Some(role) = option.get("role") {
+        check::<Role>(entries, format!("{path}.role"), UnknownEntryKind::Role, role);
+    }
+    for (index, conclusion) in array_at(option, "winIfAny") {
+        check::<GameConclusion>(entries, format!("{path}.winIfAny[{index}]"), UnknownEntryKind::GameConclusion, conclusion);
+    }
+    for (index, insider_group) in array_at(option, "insiderGroups") {
+        check::<InsiderGroupID>(entries, format!("{path}.insiderGroups[{index}]"), UnknownEntryKind::InsiderGroup, insider_group);
+    }
+}
+
+fn check<T: DeserializeOwned>(entries: &mut Vec<UnknownEntry>, path: String, kind: UnknownEntryKind, value: &Value) {
+    if serde_json::from_value::<T>(value.clone()).is_err() {
+        entries.push(UnknownEntry {
+            path,
+            kind,
+            value: value.as_str().map(str::to_string).unwrap_or_else(|| value.to_string())
+        });
+    }
+}
+
+fn array_at<'a>(value: &'a Value, key: &str) -> impl Iterator<Item = (usize, &'a Value)> {
+    value.get(key).and_then(Value::as_array).into_iter().flatten().enumerate()
+}
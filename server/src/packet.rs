@@ -25,17 +25,17 @@ use crate::{
     game::{
         ability_input::*,
         available_buttons::AvailableButtons,
-        chat::{ChatGroup, ChatMessage},
-        components::insider_group::InsiderGroupID,
-        grave::Grave, modifiers::ModifierType, phase::{PhaseState, PhaseType},
+        chat::{ChatFilter, ChatFilterError, ChatGroup, ChatMessage, ChatMessageID},
+        components::{insider_group::{CustomInsiderGroup, CustomInsiderGroupError, InsiderGroupID}, night_resolution_trace::NightTrace, nomination_ties::NominationTieBreak, will_history::WillVersion, game_history::GameHistoryExport},
+        game_conclusion::{CustomFaction, CustomFactionError}, grave::Grave, modifiers::{plurality_elimination::PluralityElimination, ModifierParametersError, ModifierState, ModifierType, Modifiers}, phase::{PhaseState, PhaseType},
         player::{PlayerIndex, PlayerReference}, 
         role::{
             doomsayer::DoomsayerGuess,
             ClientRoleStateEnum, Role
         },
-        role_list::{RoleList, RoleOutline}, settings::PhaseTimeSettings,
-        tag::Tag, verdict::Verdict, Game, GameOverReason, RejectStartReason
-    }, listener::RoomCode, lobby::lobby_client::{LobbyClient, LobbyClientID}, log, vec_map::VecMap, vec_set::VecSet
+        role_list::{CustomRoleSet, CustomRoleSetError, RoleList, RoleListConstraint, RoleListConstraintError, RoleListParseError, RoleOutline}, role_list_analysis::RoleListAnalysis, settings::{PhaseTimeSettings, RoleConfiguration, Settings, SpectatorSettings, WhisperSettings},
+        tag::Tag, verdict::Verdict, vote_weight::VoteWeight, will_template::WillTemplate, Game, GameOverReason, RejectStartReason
+    }, listener::RoomCode, lobby::{chat_report::ChatReport, lobby_client::{LobbyClient, LobbyClientID}, presets::{PresetError, PresetSummary}, settings_code::SettingsCodeError}, log, vec_map::VecMap, vec_set::VecSet
 };
 
 #[derive(Serialize, Debug, Clone)]
@@ -87,6 +87,25 @@ pub enum ToClientPacket{
     #[serde(rename_all = "camelCase")]
     RoleOutline{index: u8, role_outline: RoleOutline},
     #[serde(rename_all = "camelCase")]
+    RoleListConstraints{constraints: Vec<RoleListConstraint>},
+    RejectRoleListConstraints{reason: RoleListConstraintError},
+    #[serde(rename_all = "camelCase")]
+    RoleListAnalysis{analysis: RoleListAnalysis},
+    #[serde(rename_all = "camelCase")]
+    CustomRoleSets{custom_role_sets: Vec<CustomRoleSet>},
+    RejectCustomRoleSets{reason: CustomRoleSetError},
+    #[serde(rename_all = "camelCase")]
+    CustomInsiderGroups{custom_insider_groups: Vec<CustomInsiderGroup>},
+    RejectCustomInsiderGroups{reason: CustomInsiderGroupError},
+    #[serde(rename_all = "camelCase")]
+    CustomFactions{custom_factions: Vec<CustomFaction>},
+    RejectCustomFactions{reason: CustomFactionError},
+    Presets{presets: Vec<PresetSummary>},
+    RejectPreset{reason: PresetError},
+    SettingsCode{code: String},
+    RejectRoleList{error: RoleListParseError},
+    RejectSettingsCode{reason: SettingsCodeError},
+    #[serde(rename_all = "camelCase")]
     PhaseTime{phase: PhaseType, time: u64},
     #[serde(rename_all = "camelCase")]
     PhaseTimes{phase_time_settings: PhaseTimeSettings},
@@ -94,6 +113,23 @@ pub enum ToClientPacket{
     EnabledRoles{roles: Vec<Role>},
     #[serde(rename_all = "camelCase")]
     EnabledModifiers{modifiers: Vec<ModifierType>},
+    /// The parameters of every enabled modifier
+    #[serde(rename_all = "camelCase")]
+    ModifierParameters{modifiers: Vec<ModifierState>},
+    RejectModifierParameters{reason: ModifierParametersError},
+    #[serde(rename_all = "camelCase")]
+    RoleConfigurations{role_configurations: VecMap<Role, RoleConfiguration>},
+    RejectRoleConfigurations{role: Role},
+    #[serde(rename_all = "camelCase")]
+    NominationTieBreak{tie_break: NominationTieBreak},
+    #[serde(rename_all = "camelCase")]
+    SpectatorSettings{spectator_settings: SpectatorSettings},
+    #[serde(rename_all = "camelCase")]
+    WhisperSettings{whisper_settings: WhisperSettings},
+    #[serde(rename_all = "camelCase")]
+    ChatFilter{chat_filter: ChatFilter},
+    RejectChatFilter{reason: ChatFilterError},
+    ModerationLog{reports: Vec<ChatReport>},
 
     // Game
     
@@ -146,12 +182,20 @@ pub enum ToClientPacket{
 
     #[serde(rename_all = "camelCase")]
     AddChatMessages{chat_messages: Vec<ChatMessage>},
+    ChatMessageDeleted{id: ChatMessageID},
     AddGrave{grave: Grave},
+    /// Only sent to spectators with the omniscient view, before anything can obscure the grave
+    UnobscuredGrave{grave: Grave},
+    /// Only sent to spectators with the omniscient view
+    PlayerRoles{roles: Vec<Role>},
 
     #[serde(rename_all = "camelCase")]
     NightMessages{chat_messages: Vec<ChatMessage>},
 
     GameOver{reason: GameOverReason},
+    NightResolutionTrace{nights: Vec<NightTrace>},
+    WillHistory{versions: Vec<WillVersion>},
+    GameHistory{history: GameHistoryExport},
 }
 impl ToClientPacket {
     pub fn to_json_string(&self) -> Result<String, serde_json::Error> {
@@ -160,23 +204,24 @@ impl ToClientPacket {
             err
         })
     }
+    pub fn new_enabled_modifiers(settings: &Settings)->ToClientPacket{
+        ToClientPacket::EnabledModifiers { modifiers: settings.enabled_modifiers.keys().cloned().collect() }
+    }
+    pub fn new_modifier_parameters(settings: &Settings)->ToClientPacket{
+        ToClientPacket::ModifierParameters { modifiers: settings.enabled_modifiers.values().cloned().collect() }
+    }
+    pub fn new_player_roles(game: &Game)->ToClientPacket{
+        ToClientPacket::PlayerRoles { roles: PlayerReference::all_players(game).map(|player| player.role(game)).collect() }
+    }
     pub fn new_player_votes(game: &mut Game)->ToClientPacket{
-        let mut voted_for_player: VecMap<PlayerIndex, u8> = VecMap::new();
-
-
-        for player_ref in PlayerReference::all_players(game){
-            if player_ref.alive(game){
-                if let Some(player_voted) = player_ref.chosen_vote(game){
-                    if let Some(num_votes) = voted_for_player.get_mut(&player_voted.index()){
-                        *num_votes+=1;
-                    }else{
-                        voted_for_player.insert(player_voted.index(), 1);
-                    }
-                }
-            }
+        let votes_for_player = if Modifiers::modifier_is_enabled(game, ModifierType::PluralityElimination) {
+            PluralityElimination::votes(game)
+        }else{
+            VoteWeight::nomination_votes(game)
+        };
+        ToClientPacket::PlayerVotes { votes_for_player: 
+            PlayerReference::ref_vec_map_to_index(votes_for_player)
         }
-
-        ToClientPacket::PlayerVotes { votes_for_player: voted_for_player }
     }
 }
 
@@ -214,13 +259,25 @@ pub enum ToServerPacket{
     ReadyUp{ready: bool},
     SetLobbyName{name: String},
     StartGame,
+    /// Parsed in the lobby with [`RoleList::from_json`] so the host can be told what's wrong with it
     #[serde(rename_all = "camelCase")]
-    SetRoleList{role_list: RoleList},
+    SetRoleList{role_list: serde_json::Value},
+    /// Parsed in the lobby with [`RoleOutline::from_json`] so the host can be told what's wrong with it
     #[serde(rename_all = "camelCase")]
-    SetRoleOutline{index: u8, role_outline: RoleOutline},
+    SetRoleOutline{index: u8, role_outline: serde_json::Value},
     #[serde(rename_all = "camelCase")]
     SimplifyRoleList,
     #[serde(rename_all = "camelCase")]
+    SetRoleListConstraints{constraints: Vec<RoleListConstraint>},
+    #[serde(rename_all = "camelCase")]
+    AnalyzeRoleList,
+    #[serde(rename_all = "camelCase")]
+    SetCustomRoleSets{custom_role_sets: Vec<CustomRoleSet>},
+    #[serde(rename_all = "camelCase")]
+    SetCustomInsiderGroups{custom_insider_groups: Vec<CustomInsiderGroup>},
+    #[serde(rename_all = "camelCase")]
+    SetCustomFactions{custom_factions: Vec<CustomFaction>},
+    #[serde(rename_all = "camelCase")]
     SetPhaseTime{phase: PhaseType, time: u64},
     #[serde(rename_all = "camelCase")]
     SetPhaseTimes{phase_time_settings: PhaseTimeSettings},
@@ -228,6 +285,29 @@ pub enum ToServerPacket{
     SetEnabledRoles{roles: Vec<Role>},
     #[serde(rename_all = "camelCase")]
     SetEnabledModifiers{modifiers: Vec<ModifierType>},
+    /// Only changes a modifier that's already enabled
+    #[serde(rename_all = "camelCase")]
+    SetModifierParameters{modifier: ModifierState},
+    #[serde(rename_all = "camelCase")]
+    SetRoleConfigurations{role_configurations: VecMap<Role, RoleConfiguration>},
+    #[serde(rename_all = "camelCase")]
+    SetNominationTieBreak{tie_break: NominationTieBreak},
+    #[serde(rename_all = "camelCase")]
+    SetSpectatorSettings{spectator_settings: SpectatorSettings},
+    #[serde(rename_all = "camelCase")]
+    SetWhisperSettings{whisper_settings: WhisperSettings},
+    #[serde(rename_all = "camelCase")]
+    SetChatFilter{chat_filter: ChatFilter},
+    #[serde(rename_all = "camelCase")]
+    ListPresets{owner_key: String},
+    #[serde(rename_all = "camelCase")]
+    LoadPreset{owner_key: String, name: String},
+    #[serde(rename_all = "camelCase")]
+    SavePreset{owner_key: String, name: String},
+    #[serde(rename_all = "camelCase")]
+    DeletePreset{owner_key: String, name: String},
+    ExportSettings,
+    ImportSettings{code: String},
 
     BackToLobby,
 
@@ -236,10 +316,28 @@ pub enum ToServerPacket{
     Vote{player_index: Option<PlayerIndex>},
     Judgement{verdict: Verdict},
 
-    SendChatMessage{text: String, block: bool},
+    #[serde(rename_all = "camelCase")]
+    SendChatMessage{
+        text: String,
+        block: bool,
+        #[serde(default)]
+        reply_to: Option<ChatMessageID>,
+        #[serde(default)]
+        mentions: Vec<PlayerIndex>
+    },
     #[serde(rename_all = "camelCase")]
     SendWhisper{player_index: PlayerIndex, text: String},
+    /// Host only
+    DeleteChatMessage{id: ChatMessageID},
+    /// Host only, muted players can't talk in the all chat
+    #[serde(rename_all = "camelCase")]
+    SetPlayerMuted{player_index: PlayerIndex, muted: bool},
+    #[serde(rename_all = "camelCase")]
+    ReportPlayer{player_index: PlayerIndex, reason: String},
+    /// Host only
+    GetModerationLog,
     SaveWill{will: String},
+    SaveWillTemplate{template: WillTemplate},
     SaveNotes{notes: Vec<String>},
     #[serde(rename_all = "camelCase")]
     SaveCrossedOutOutlines{crossed_out_outlines: Vec<u8>},
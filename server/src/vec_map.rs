@@ -11,7 +11,7 @@ impl<K, V> Default for VecMap<K, V> where K: Eq {
 }
 
 impl<K, V> VecMap<K, V> where K: Eq {
-    pub fn new() -> Self {
+    pub const fn new() -> Self {
         VecMap { vec: Vec::new() }
     }
     pub fn new_from_vec(vec: Vec<(K, V)>) -> Self {
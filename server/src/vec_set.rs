@@ -13,7 +13,7 @@ impl<K> Default for VecSet<K> where K: Eq {
 }
 
 impl <K> VecSet<K> where K: Eq {
-    pub fn new() -> Self {
+    pub const fn new() -> Self {
         VecSet { vec: VecMap::new() }
     }
 
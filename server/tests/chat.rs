@@ -0,0 +1,109 @@
+#[allow(unused)]
+mod kit;
+
+pub(crate) use kit::assert_contains;
+
+use mafia_server::{
+    game::{
+        chat::{ChatFilter, ChatFilterError, ChatFilterMode, ChatGroup, ChatMessageVariant, MessageSender},
+        components::muted::Muted,
+        phase::PhaseType,
+        role::{detective::Detective, jester::Jester, mafioso::Mafioso, RoleState}
+    },
+    lobby::chat_report::ChatReport,
+    packet::ToServerPacket
+};
+
+#[test]
+fn chat_filter_masks_or_blocks_whole_words() {
+    kit::scenario!(game in Discussion 2 where
+        townie: Detective,
+        mafioso: Mafioso
+    );
+    game.settings.chat_filter = ChatFilter { mode: ChatFilterMode::Mask, words: vec!["darn".to_string()] };
+    assert!(game.settings.chat_filter.check().is_ok());
+    assert_eq!(game.settings.chat_filter.apply("Darn, darnit"), Some("****, darnit".to_string()));
+
+    townie.send_message("oh darn");
+    assert_contains!(mafioso.get_messages(), ChatMessageVariant::Normal {
+        message_sender: MessageSender::Player { player: townie.index() },
+        text: "oh ****".to_string(),
+        block: false,
+        reply_to: None,
+        mentions: vec![]
+    });
+
+    game.settings.chat_filter.mode = ChatFilterMode::Block;
+    mafioso.send_message("DARN it");
+    assert_contains!(mafioso.get_messages(), ChatMessageVariant::BlockedByChatFilter);
+    assert!(townie.get_messages().iter().all(|message| !matches!(message, ChatMessageVariant::Normal { text, .. } if text.contains("it"))));
+
+    assert_eq!(
+        ChatFilter { mode: ChatFilterMode::Block, words: vec!["two words".to_string()] }.check(),
+        Err(ChatFilterError::InvalidWord { word: 0 })
+    );
+}
+
+#[test]
+fn muted_players_cant_talk_in_all_chat_and_reports_keep_their_messages() {
+    kit::scenario!(game in Discussion 2 where
+        townie: Detective,
+        mafioso: Mafioso
+    );
+    mafioso.send_message("first");
+    Muted::set_muted(&mut game, mafioso.player_ref(), true);
+    assert!(!mafioso.player_ref().get_current_send_chat_groups(&game).contains(&ChatGroup::All));
+    assert_contains!(townie.get_messages(), ChatMessageVariant::PlayerMuted { player_index: mafioso.index(), muted: true });
+
+    mafioso.send_message("second");
+    mafioso.send_whisper(townie, "third");
+    assert!(townie.get_messages().iter().all(|message| !matches!(message, ChatMessageVariant::Normal { text, .. } | ChatMessageVariant::Whisper { text, .. } if text == "second" || text == "third")));
+
+    let report = ChatReport::new(&game, 1, townie.player_ref(), mafioso.player_ref(), "  rude ".to_string());
+    assert_eq!(report.reason, "rude");
+    assert_eq!(report.reported_name, *mafioso.player_ref().name(&game));
+    assert_eq!(report.messages.len(), 1);
+
+    Muted::set_muted(&mut game, mafioso.player_ref(), false);
+    assert!(mafioso.player_ref().get_current_send_chat_groups(&game).contains(&ChatGroup::All));
+}
+
+#[test]
+fn replies_and_mentions_are_checked_and_notify_mentioned_players() {
+    kit::scenario!(game in Discussion 2 where
+        townie: Detective,
+        mafioso: Mafioso,
+        jester: Jester
+    );
+    townie.send_message("first");
+    let first = mafioso.player_ref().chat_messages(&game).last().unwrap().id;
+
+    game.on_client_message(mafioso.index(), ToServerPacket::SendChatMessage {
+        text: "reply".to_string(),
+        block: false,
+        reply_to: Some(first),
+        mentions: vec![townie.index(), 200, townie.index()]
+    });
+    let reply = ChatMessageVariant::Normal {
+        message_sender: MessageSender::Player { player: mafioso.index() },
+        text: "reply".to_string(),
+        block: false,
+        reply_to: Some(first),
+        mentions: vec![townie.index()]
+    };
+    let notified = |player: kit::player::TestPlayer, game: &mafia_server::game::Game| player.player_ref().chat_messages(game).iter()
+        .find(|message| message.variant == reply)
+        .map(|message| message.notify);
+    assert_eq!(notified(townie, &game), Some(true));
+    assert_eq!(notified(jester, &game), Some(false));
+
+    game.on_client_message(jester.index(), ToServerPacket::SendChatMessage {
+        text: "bad reply".to_string(),
+        block: false,
+        reply_to: Some(u32::MAX),
+        mentions: vec![]
+    });
+    assert!(townie.get_messages().iter().any(|message| matches!(message,
+        ChatMessageVariant::Normal { text, reply_to: None, .. } if text == "bad reply"
+    )));
+}
@@ -76,6 +76,7 @@ pub mod _init {
                     roles: RoleOutlineOptionRoles::Role { role: role.role() },
                     insider_groups: RoleOutlineOptionInsiderGroups::RoleDefault,
                     win_condition: RoleOutlineOptionWinCondition::RoleDefault,
+                    ..Default::default()
                 }]
             });
         }
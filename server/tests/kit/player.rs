@@ -103,13 +103,27 @@ impl TestPlayer {
             !Modifiers::modifier_is_enabled(game!(self), ModifierType::ScheduledNominations)
         );
     }
+    pub fn vote_for_elimination(&self, target: TestPlayer) {
+        self.send_ability_input(
+            AbilityInput::new(
+                ControllerID::elimination_vote(self.player_ref()),
+                AbilitySelection::new_player_list(vec![target.player_ref()])
+            )
+        );
+    }
     pub fn set_verdict(&self, verdict: Verdict) {
         self.0.set_verdict(game!(self), verdict);
     }
 
     pub fn send_message(&self, message: &str) {
         game!(self).on_client_message(self.0.index(), 
-            ToServerPacket::SendChatMessage { text: message.to_string(), block: false }
+            ToServerPacket::SendChatMessage { text: message.to_string(), block: false, reply_to: None, mentions: vec![] }
+        );
+    }
+
+    pub fn send_whisper(&self, whisperee: TestPlayer, message: &str) {
+        game!(self).on_client_message(self.0.index(), 
+            ToServerPacket::SendWhisper { player_index: whisperee.index(), text: message.to_string() }
         );
     }
 
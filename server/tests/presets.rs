@@ -0,0 +1,81 @@
+use std::{fs, path::PathBuf};
+
+use mafia_server::{
+    game::{role::Role, role_list::{RoleList, RoleOutline}, settings::Settings},
+    lobby::presets::{PresetError, PresetLibrary, PresetSummary}
+};
+
+const OWNER_KEY: &str = "test-owner-key";
+
+fn library(test_name: &str) -> (PresetLibrary, PathBuf) {
+    let directory = std::env::temp_dir().join(format!("mafia_presets_{}_{}", test_name, std::process::id()));
+    let _ = fs::remove_dir_all(&directory);
+    (PresetLibrary::new(&directory), directory)
+}
+
+#[test]
+fn built_in_presets_are_listed_and_load() {
+    let (library, directory) = library("built_in");
+
+    let presets = library.list(OWNER_KEY).expect("listing should work without saved presets");
+    assert!(presets.iter().any(|preset| preset.name == "Classic (15)" && preset.built_in));
+
+    let settings = library.load(OWNER_KEY, "Classic (15)").expect("built in preset should load");
+    assert_eq!(settings.role_list.0.len(), 15);
+    assert_eq!(library.delete(OWNER_KEY, "Classic (15)"), Err(PresetError::BuiltIn));
+
+    let _ = fs::remove_dir_all(directory);
+}
+
+#[test]
+fn presets_can_be_saved_loaded_and_deleted() {
+    let (library, directory) = library("saved");
+    let settings = Settings {
+        role_list: RoleList(vec![RoleOutline::new_exact(Role::Jester), RoleOutline::new_exact(Role::Mafioso)]),
+        enabled_roles: vec![Role::Jester, Role::Mafioso].into_iter().collect(),
+        ..Default::default()
+    };
+
+    library.save(OWNER_KEY, "My List", &settings).expect("preset should save");
+    assert!(library.list(OWNER_KEY).unwrap().contains(&PresetSummary { name: "My List".to_string(), built_in: false }));
+    assert_eq!(library.load(OWNER_KEY, "My List").unwrap().role_list, settings.role_list);
+    assert_eq!(library.load("another-owner-key", "My List").err(), Some(PresetError::NotFound));
+
+    library.delete(OWNER_KEY, "My List").expect("preset should delete");
+    assert_eq!(library.load(OWNER_KEY, "My List").err(), Some(PresetError::NotFound));
+
+    let _ = fs::remove_dir_all(directory);
+}
+
+#[test]
+fn presets_reject_unsafe_names_and_newer_versions() {
+    let (library, directory) = library("invalid");
+
+    assert_eq!(library.save(OWNER_KEY, "../escape", &Settings::default()), Err(PresetError::InvalidName));
+    assert_eq!(library.save("../..", "Fine", &Settings::default()), Err(PresetError::InvalidOwnerKey));
+
+    fs::create_dir_all(directory.join(OWNER_KEY)).unwrap();
+    fs::write(directory.join(OWNER_KEY).join("Future.json"), r#"{"version": 255, "name": "Future", "settings": {}}"#).unwrap();
+    assert_eq!(library.load(OWNER_KEY, "Future").err(), Some(PresetError::UnsupportedVersion));
+
+    let _ = fs::remove_dir_all(directory);
+}
+
+#[test]
+fn presets_are_listed_by_file_name_and_large_files_are_rejected() {
+    let (library, directory) = library("listing");
+    let owner_directory = directory.join(OWNER_KEY);
+    fs::create_dir_all(&owner_directory).unwrap();
+
+    fs::write(owner_directory.join("Renamed.json"), r#"{"version": 1, "name": "Something Else", "settings": {}}"#).unwrap();
+    let presets = library.list(OWNER_KEY).unwrap();
+    assert!(presets.contains(&PresetSummary { name: "Renamed".to_string(), built_in: false }));
+    assert!(!presets.iter().any(|preset| preset.name == "Something Else"));
+    assert!(library.load(OWNER_KEY, "Renamed").is_ok());
+
+    fs::write(owner_directory.join("Huge.json"), " ".repeat(100 * 1024)).unwrap();
+    assert!(!library.list(OWNER_KEY).unwrap().iter().any(|preset| preset.name == "Huge"));
+    assert_eq!(library.load(OWNER_KEY, "Huge").err(), Some(PresetError::TooLarge));
+
+    let _ = fs::remove_dir_all(directory);
+}
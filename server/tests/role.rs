@@ -1,11 +1,13 @@
 mod kit;
-use std::{ops::Deref, vec};
+use std::{ops::Deref, time::Duration, vec};
 
 pub(crate) use kit::{assert_contains, assert_not_contains};
 
-use mafia_server::game::{ability_input::{ability_selection::AbilitySelection, ControllerID}, game_conclusion::GameConclusion, role::engineer::Trap};
+use mafia_server::game::components::game_history::{GameHistory, GameHistoryEventKind};
+use mafia_server::game::{ability_input::{ability_selection::AbilitySelection, ControllerID}, game_conclusion::{CustomFaction, CustomFactionError, GameConclusion}, modifiers::{plurality_elimination::{EliminationTieBreak, PluralityElimination}, random_love_links::RandomLoveLinks, role_set_grave_killers::RoleSetGraveKillers, two_thirds_majority::TwoThirdsMajority, ModifierState, ModifierType, Modifiers}, role::engineer::Trap};
 pub use mafia_server::game::{
-    chat::{ChatMessageVariant, MessageSender, ChatGroup}, 
+    chat::{ChatMessage, ChatMessageVariant, MessageSender, ChatGroup}, 
+    spectator::SpectatorInitializeParameters,
     grave::*,
     ability_input::{
         selection_type::{
@@ -14,12 +16,17 @@ pub use mafia_server::game::{
         },
         AbilityInput,
     }, 
-    components::{cult::CultAbility, insider_group::InsiderGroupID},  
-    role_list::RoleSet, 
+    components::{cult::CultAbility, insider_group::{CustomInsiderGroup, CustomInsiderGroupError, InsiderGroupID}, love_linked::LoveLinked, night_resolution_trace::{AttackOutcome, NightResolutionTrace, NightTraceEvent}, nomination_ties::NominationTieBreak},  
+    role_list::{role_can_generate, CustomRoleSet, CustomRoleSetError, RoleList, RoleListConstraint, RoleOutline, RoleOutlineOption, RoleOutlineOptionInsiderGroups, RoleOutlineOptionRoles, RoleOutlineOptionWinCondition, RoleSet},
+    role_list_analysis::RoleListAnalysis,
+    role_list_generation::RoleListGenerationError,
+    settings::{RoleConfiguration, Settings, WhisperSettings},
+    whisper::Whispers,
     role_outline_reference::RoleOutlineReference,
      
     player::PlayerReference,
     tag::Tag,
+    attack_power::{AttackPower, DefensePower},
     verdict::Verdict,
     role::{
         Role,
@@ -60,6 +67,7 @@ pub use mafia_server::game::{
         impostor::Impostor,
         recruiter::Recruiter,
         counterfeiter::Counterfeiter,
+        forger::Forger,
         mafioso::Mafioso,
         
         framer::Framer,
@@ -104,6 +112,7 @@ pub use mafia_server::game::{
 };
 // Pub use so that submodules don't have to reimport everything.
 pub use mafia_server::packet::ToServerPacket;
+pub use mafia_server::client_connection::ClientConnection;
 
 #[test]
 fn no_unwanted_tags() {
@@ -677,6 +686,31 @@ fn mayor_reveals_after_they_vote(){
     assert_eq!(game.current_phase().phase(), Testimony);
 }
 
+#[test]
+fn revealed_mayor_verdict_counts_three_times(){
+    kit::scenario!(game where
+        mayor: Mayor,
+        townie: Detective,
+        townie2: Detective,
+        townie3: Detective,
+        mafioso: Mafioso
+    );
+
+    game.skip_to(Nomination, 2);
+    mayor.send_ability_input_unit_typical();
+    assert_contains!(townie.get_player_tags().get(&mayor.player_ref()).expect("mayor should be tagged"), Tag::Enfranchised);
+
+    townie.vote_for_player(Some(mafioso));
+    townie2.vote_for_player(Some(mafioso));
+    townie3.vote_for_player(Some(mafioso));
+    game.skip_to(Judgement, 2);
+
+    mayor.set_verdict(Verdict::Guilty);
+    townie.set_verdict(Verdict::Innocent);
+    townie2.set_verdict(Verdict::Innocent);
+    assert_eq!(game.count_verdict_votes(mafioso.player_ref()), (3, 2));
+}
+
 
 #[test]
 fn retributionist_basic(){
@@ -937,7 +971,9 @@ fn can_type_in_jail() {
         ChatMessageVariant::Normal { 
             message_sender: MessageSender::Player { player: detective.index() }, 
             text: "Hello!".to_string(),
-            block: false
+            block: false,
+            reply_to: None,
+            mentions: vec![]
         }
     );
     
@@ -945,7 +981,9 @@ fn can_type_in_jail() {
         ChatMessageVariant::Normal { 
             message_sender: MessageSender::Player { player: detective.index() }, 
             text: "Hello!".to_string(),
-            block: false
+            block: false,
+            reply_to: None,
+            mentions: vec![]
         }
     );
 }
@@ -2634,4 +2672,828 @@ fn santa_always_gets_their_naughty_selection() {
             GameConclusion::NaughtyList
         );
     }
-}
\ No newline at end of file
+}
+
+#[test]
+fn plurality_elimination_eliminates_top_vote_getter() {
+    kit::scenario!(game where
+        townie: Detective,
+        townie2: Detective,
+        townie3: Detective,
+        mafioso: Mafioso
+    );
+    Modifiers::set_modifier(&mut game, ModifierType::PluralityElimination.default_state());
+
+    game.skip_to(Nomination, 2);
+    townie.vote_for_elimination(mafioso);
+    townie2.vote_for_elimination(mafioso);
+    mafioso.vote_for_elimination(townie3);
+    assert_eq!(game.current_phase().phase(), Nomination);
+
+    game.next_phase();
+    assert_eq!(*game.current_phase(), PhaseState::FinalWords { player_on_trial: mafioso.player_ref() });
+    game.next_phase();
+    assert!(!mafioso.alive());
+    assert!(townie3.alive());
+}
+
+#[test]
+fn plurality_elimination_tie_without_tie_break() {
+    kit::scenario!(game where
+        townie: Detective,
+        _townie2: Detective,
+        townie3: Detective,
+        mafioso: Mafioso
+    );
+    Modifiers::set_modifier(&mut game, ModifierType::PluralityElimination.default_state());
+
+    game.skip_to(Nomination, 2);
+    townie.vote_for_elimination(mafioso);
+    mafioso.vote_for_elimination(townie3);
+
+    game.next_phase();
+    assert_eq!(game.current_phase().phase(), Dusk);
+    assert!(mafioso.alive());
+    assert!(townie3.alive());
+}
+
+#[test]
+fn plurality_elimination_runoff_between_tied_players() {
+    kit::scenario!(game where
+        townie: Detective,
+        townie2: Detective,
+        townie3: Detective,
+        mafioso: Mafioso
+    );
+    Modifiers::set_modifier(&mut game, ModifierState::PluralityElimination(PluralityElimination::new(EliminationTieBreak::Runoff)));
+
+    game.skip_to(Nomination, 2);
+    townie.vote_for_elimination(mafioso);
+    mafioso.vote_for_elimination(townie3);
+
+    game.next_phase();
+    assert_eq!(game.current_phase().phase(), Nomination);
+    assert_contains!(townie.get_messages(), ChatMessageVariant::EliminationRunoff {
+        tied_players: vec![mafioso.index(), townie3.index()]
+    });
+
+    // Only the tied players can be voted for in the runoff
+    townie.vote_for_elimination(townie2);
+    townie2.vote_for_elimination(mafioso);
+    townie3.vote_for_elimination(mafioso);
+
+    game.next_phase();
+    assert_eq!(*game.current_phase(), PhaseState::FinalWords { player_on_trial: mafioso.player_ref() });
+}
+
+#[test]
+fn secret_ballot_hides_individual_votes() {
+    kit::scenario!(game where
+        townie: Detective,
+        townie2: Detective,
+        townie3: Detective,
+        mafioso: Mafioso
+    );
+    Modifiers::set_modifier(&mut game, ModifierType::SecretBallot.default_state());
+
+    game.skip_to(Nomination, 2);
+    townie.vote_for_player(Some(mafioso));
+    townie2.vote_for_player(Some(mafioso));
+    townie3.vote_for_player(Some(mafioso));
+    assert_eq!(game.current_phase().phase(), Testimony);
+
+    let townie_vote = ChatMessageVariant::Voted { voter: townie.index(), votee: Some(mafioso.index()) };
+    assert_contains!(townie.get_messages(), townie_vote);
+    assert_not_contains!(townie2.get_messages(), townie_vote);
+    assert_contains!(townie2.get_messages(), ChatMessageVariant::PlayerNominated {
+        player_index: mafioso.index(),
+        players_voted: vec![]
+    });
+
+    game.skip_to(Judgement, 2);
+    townie.set_verdict(Verdict::Guilty);
+    townie2.set_verdict(Verdict::Guilty);
+    townie3.set_verdict(Verdict::Innocent);
+    game.next_phase();
+
+    let townie3_verdict = ChatMessageVariant::JudgementVerdict { voter_player_index: townie3.index(), verdict: Verdict::Innocent };
+    assert_contains!(townie3.get_messages(), townie3_verdict);
+    assert_not_contains!(townie.get_messages(), townie3_verdict);
+    assert_contains!(townie.get_messages(), ChatMessageVariant::TrialVerdict {
+        player_on_trial: mafioso.index(),
+        innocent: 1,
+        guilty: 2
+    });
+}
+
+#[test]
+fn nomination_tie_without_tie_break_has_no_trial() {
+    kit::scenario!(game where
+        mayor: Mayor,
+        townie: Detective,
+        townie2: Detective,
+        townie3: Detective,
+        mafioso: Mafioso
+    );
+    Modifiers::set_modifier(&mut game, ModifierType::ScheduledNominations.default_state());
+
+    game.skip_to(Nomination, 2);
+    mayor.send_ability_input_unit_typical();
+    mayor.vote_for_player(Some(mafioso));
+    townie.vote_for_player(Some(townie3));
+    townie2.vote_for_player(Some(townie3));
+    mafioso.vote_for_player(Some(townie3));
+
+    assert_eq!(game.count_nomination_and_start_trial(false), None);
+}
+
+#[test]
+fn nomination_tie_random_puts_a_tied_player_on_trial() {
+    kit::scenario!(game where
+        mayor: Mayor,
+        townie: Detective,
+        townie2: Detective,
+        townie3: Detective,
+        mafioso: Mafioso
+    );
+    Modifiers::set_modifier(&mut game, ModifierType::ScheduledNominations.default_state());
+    game.settings.nomination_tie_break = NominationTieBreak::Random;
+
+    game.skip_to(Nomination, 2);
+    mayor.send_ability_input_unit_typical();
+    mayor.vote_for_player(Some(mafioso));
+    townie.vote_for_player(Some(townie3));
+    townie2.vote_for_player(Some(townie3));
+    mafioso.vote_for_player(Some(townie3));
+
+    game.next_phase();
+    let PhaseState::Testimony { player_on_trial, .. } = *game.current_phase() else {
+        panic!("expected a trial, got {:?}", game.current_phase());
+    };
+    assert!(player_on_trial == mafioso.player_ref() || player_on_trial == townie3.player_ref());
+    assert_contains!(townie.get_messages(), ChatMessageVariant::NominationTieBroken {
+        tied_players: vec![mafioso.index(), townie3.index()],
+        player_index: player_on_trial.index()
+    });
+}
+
+#[test]
+fn scheduled_nomination_tie_is_only_broken_once_when_the_trial_starts() {
+    kit::scenario!(game where
+        mayor: Mayor,
+        townie: Detective,
+        townie2: Detective,
+        townie3: Detective,
+        mafioso: Mafioso
+    );
+    Modifiers::set_modifier(&mut game, ModifierType::ScheduledNominations.default_state());
+    game.settings.nomination_tie_break = NominationTieBreak::Random;
+
+    game.skip_to(Nomination, 2);
+    mayor.send_ability_input_unit_typical();
+    mayor.vote_for_player(Some(mafioso));
+    townie.vote_for_player(Some(townie3));
+    townie2.vote_for_player(Some(townie3));
+    mafioso.vote_for_player(Some(townie3));
+    // Votes are counted again every time they change, while the tie is still there
+    townie2.vote_for_player(None);
+    townie2.vote_for_player(Some(townie3));
+    assert_eq!(game.current_phase().phase(), Nomination);
+
+    game.next_phase();
+    let tie_broken_messages = townie.get_messages().iter()
+        .filter(|message| matches!(message, ChatMessageVariant::NominationTieBroken { .. }))
+        .count();
+    assert_eq!(tie_broken_messages, 1);
+}
+
+#[test]
+fn nomination_tie_earliest_to_threshold_puts_first_player_on_trial() {
+    kit::scenario!(game where
+        mayor: Mayor,
+        townie: Detective,
+        townie2: Detective,
+        townie3: Detective,
+        mafioso: Mafioso
+    );
+    Modifiers::set_modifier(&mut game, ModifierType::ScheduledNominations.default_state());
+    game.settings.nomination_tie_break = NominationTieBreak::EarliestToThreshold;
+
+    game.skip_to(Nomination, 2);
+    mayor.send_ability_input_unit_typical();
+    townie.vote_for_player(Some(townie3));
+    townie2.vote_for_player(Some(townie3));
+    mafioso.vote_for_player(Some(townie3));
+    mayor.vote_for_player(Some(mafioso));
+
+    game.next_phase();
+    let PhaseState::Testimony { player_on_trial, .. } = *game.current_phase() else {
+        panic!("expected a trial, got {:?}", game.current_phase());
+    };
+    assert_eq!(player_on_trial, townie3.player_ref());
+}
+
+#[test]
+fn nomination_tie_revote_between_tied_players() {
+    kit::scenario!(game where
+        mayor: Mayor,
+        townie: Detective,
+        townie2: Detective,
+        townie3: Detective,
+        mafioso: Mafioso
+    );
+    Modifiers::set_modifier(&mut game, ModifierType::ScheduledNominations.default_state());
+    game.settings.nomination_tie_break = NominationTieBreak::Revote;
+
+    game.skip_to(Nomination, 2);
+    mayor.send_ability_input_unit_typical();
+    mayor.vote_for_player(Some(mafioso));
+    townie.vote_for_player(Some(townie3));
+    townie2.vote_for_player(Some(townie3));
+    mafioso.vote_for_player(Some(townie3));
+
+    game.next_phase();
+    assert_eq!(game.current_phase().phase(), Nomination);
+    assert_contains!(townie.get_messages(), ChatMessageVariant::NominationRevote {
+        tied_players: vec![mafioso.index(), townie3.index()]
+    });
+
+    // Only the tied players can be voted for in the revote
+    townie.vote_for_player(Some(townie2));
+    assert_eq!(townie.player_ref().chosen_vote(game.deref()), None);
+
+    mayor.vote_for_player(Some(mafioso));
+    townie.vote_for_player(Some(mafioso));
+
+    game.next_phase();
+    let PhaseState::Testimony { player_on_trial, .. } = *game.current_phase() else {
+        panic!("expected a trial, got {:?}", game.current_phase());
+    };
+    assert_eq!(player_on_trial, mafioso.player_ref());
+}
+
+#[test]
+fn night_resolution_trace_records_roleblocks_and_attacks() {
+    kit::scenario!(game in Night 2 where
+        mafioso: Mafioso,
+        townie: Detective,
+        doctor: Doctor,
+        escort: Escort
+    );
+
+    mafioso.send_ability_input_player_list_typical(townie);
+    doctor.send_ability_input_player_list_typical(townie);
+    escort.send_ability_input_player_list_typical(doctor);
+
+    game.next_phase();
+    assert!(!townie.alive());
+
+    let night = NightResolutionTrace::nights(&game).last().expect("night 2 should be traced");
+    assert_eq!(night.day_number(), 2);
+    assert_contains!(night.events(), NightTraceEvent::Roleblocked { player: doctor.player_ref(), immune: false });
+    assert!(night.events().iter().any(|event| matches!(event,
+        NightTraceEvent::Attacked { target, attack: AttackPower::Basic, defense: DefensePower::None, outcome: AttackOutcome::Killed, .. }
+            if *target == townie.player_ref()
+    )));
+}
+
+#[test]
+fn two_thirds_majority_uses_configured_fraction() {
+    kit::scenario!(game where
+        townie: Detective,
+        townie2: Detective,
+        _townie3: Detective,
+        mafioso: Mafioso
+    );
+    Modifiers::set_modifier(&mut game, ModifierState::TwoThirdsMajority(TwoThirdsMajority { numerator: 1, denominator: 2 }));
+    assert_eq!(game.nomination_votes_required(), 2);
+
+    game.skip_to(Nomination, 2);
+    townie.vote_for_player(Some(mafioso));
+    townie2.vote_for_player(Some(mafioso));
+    assert_eq!(game.current_phase().phase(), Testimony);
+}
+
+#[test]
+fn random_love_links_creates_configured_pairs() {
+    kit::scenario!(game where
+        townie: Detective,
+        townie2: Detective,
+        townie3: Detective,
+        mafioso: Mafioso
+    );
+    let modifier = ModifierState::RandomLoveLinks(RandomLoveLinks { pairs: Some(1) });
+    Modifiers::set_modifier(&mut game, modifier);
+    Modifiers::on_game_start(&mut game);
+
+    let linked_players = [townie, townie2, townie3, mafioso].iter()
+        .filter(|player| !LoveLinked::get_links(&game, player.player_ref()).is_empty())
+        .count();
+    assert_eq!(linked_players, 2);
+}
+
+#[test]
+fn modifier_settings_serialize_with_parameters() {
+    let modifier: ModifierState = serde_json::from_str(r#"{"type":"twoThirdsMajority","numerator":3,"denominator":4}"#)
+        .expect("modifier with parameters should deserialize");
+    assert_eq!(modifier, ModifierState::TwoThirdsMajority(TwoThirdsMajority { numerator: 3, denominator: 4 }));
+
+    let modifier: ModifierState = serde_json::from_str(r#"{"type":"noChat"}"#)
+        .expect("modifier without parameters should deserialize");
+    assert_eq!(ModifierType::from(&modifier), ModifierType::NoChat);
+
+    assert!(!ModifierState::TwoThirdsMajority(TwoThirdsMajority { numerator: 4, denominator: 3 }).has_valid_parameters());
+    assert!(!ModifierState::TwoThirdsMajority(TwoThirdsMajority { numerator: 1, denominator: 1 }).has_valid_parameters());
+}
+
+#[test]
+fn two_thirds_majority_does_not_hang_without_guilty_votes() {
+    kit::scenario!(game where
+        townie: Detective,
+        townie2: Detective,
+        townie3: Detective,
+        mafioso: Mafioso
+    );
+    Modifiers::set_modifier(&mut game, ModifierState::TwoThirdsMajority(TwoThirdsMajority { numerator: 1, denominator: 2 }));
+    assert!(!TwoThirdsMajority::default().verdict_is_guilty(0, 1));
+
+    game.skip_to(Nomination, 2);
+    townie.vote_for_player(Some(mafioso));
+    townie2.vote_for_player(Some(mafioso));
+    game.skip_to(Judgement, 2);
+    townie3.set_verdict(Verdict::Innocent);
+    game.next_phase();
+
+    assert_ne!(game.current_phase().phase(), FinalWords);
+    assert!(mafioso.alive());
+}
+
+#[test]
+fn doctor_uses_configured_self_heals() {
+    kit::scenario!(game where
+        doctor: Doctor,
+        mafioso: Mafioso,
+        townie: Detective,
+        townie2: Detective
+    );
+    game.settings.role_configurations.insert(Role::Doctor, RoleConfiguration { maximum_count: None, uses: Some(2) });
+    doctor.set_role_state(Role::Doctor.new_state(&game));
+
+    game.skip_to(Night, 2);
+    doctor.send_ability_input_player_list_typical(doctor);
+    mafioso.send_ability_input_player_list_typical(doctor);
+    game.skip_to(Night, 3);
+    assert!(doctor.alive());
+
+    doctor.send_ability_input_player_list_typical(doctor);
+    mafioso.send_ability_input_player_list_typical(doctor);
+    game.next_phase();
+    assert!(doctor.alive());
+    assert!(townie.alive());
+    assert!(townie2.alive());
+}
+
+#[test]
+fn role_maximum_count_can_be_configured() {
+    let mut settings = Settings::default();
+    settings.enabled_roles.insert(Role::Deputy);
+    assert!(!role_can_generate(Role::Deputy, &settings, &[Role::Deputy]));
+
+    settings.role_configurations.insert(Role::Deputy, RoleConfiguration { maximum_count: Some(2), uses: None });
+    assert!(role_can_generate(Role::Deputy, &settings, &[Role::Deputy]));
+    assert!(!role_can_generate(Role::Deputy, &settings, &[Role::Deputy, Role::Deputy]));
+
+    assert!(!RoleConfiguration { maximum_count: None, uses: Some(2) }.is_valid_for(Role::Detective));
+}
+
+fn outline_of(roles: RoleOutlineOptionRoles) -> RoleOutline {
+    RoleOutline { options: vec1::vec1![RoleOutlineOption { roles, ..Default::default() }] }
+}
+
+#[test]
+fn role_list_analysis_reports_chances_and_instant_endings() {
+    let settings = Settings {
+        role_list: RoleList(vec![
+            outline_of(RoleOutlineOptionRoles::Role { role: Role::Detective }),
+            outline_of(RoleOutlineOptionRoles::RoleSet { role_set: RoleSet::TownInvestigative }),
+        ]),
+        enabled_roles: Role::values().into_iter().collect(),
+        ..Default::default()
+    };
+
+    let analysis = RoleListAnalysis::new(&settings);
+    assert_eq!(analysis.error, None);
+    assert!(analysis.outlines_with_no_roles.is_empty());
+    assert_eq!(analysis.role_chances.get(&Role::Detective), Some(&1.0));
+    assert_eq!(analysis.role_set_chances.get(&RoleSet::Town), Some(&1.0));
+    assert_eq!(analysis.role_set_chances.get(&RoleSet::Mafia), None);
+    assert_eq!(analysis.conclusion_chances.get(&GameConclusion::Town), Some(&1.0));
+    assert!(analysis.can_end_instantly);
+}
+
+#[test]
+fn role_list_analysis_reports_outlines_with_no_roles() {
+    let settings = Settings {
+        role_list: RoleList(vec![
+            outline_of(RoleOutlineOptionRoles::Role { role: Role::Detective }),
+            outline_of(RoleOutlineOptionRoles::Role { role: Role::Mafioso }),
+            outline_of(RoleOutlineOptionRoles::Role { role: Role::Jester }),
+        ]),
+        enabled_roles: vec![Role::Detective].into_iter().collect(),
+        ..Default::default()
+    };
+
+    let analysis = RoleListAnalysis::new(&settings);
+    assert_eq!(analysis.outlines_with_no_roles, vec![1, 2]);
+    assert_eq!(analysis.error, Some(RoleListGenerationError::OutlineHasNoRoles { outline: 1 }));
+}
+
+#[test]
+fn role_list_parsing_reports_where_it_failed() {
+    let role_list = RoleList::from_json(&serde_json::json!([
+        [{"role": "jester"}],
+        [{"roleSet": "town", "weight": 2}, {"roleSet": "twon"}]
+    ]));
+    assert_eq!(role_list.err().map(|error| (error.outline, error.option, error.field)), Some((Some(1), Some(1), Some("roleSet".to_string()))));
+
+    let role_list = RoleList::from_json(&serde_json::json!([[{"role": "jester", "winIfAnyy": ["town"]}]]));
+    assert_eq!(role_list.err().map(|error| (error.outline, error.option, error.field)), Some((Some(0), Some(0), Some("winIfAnyy".to_string()))));
+
+    let role_list = RoleList::from_json(&serde_json::json!([[]]));
+    assert_eq!(role_list.err().map(|error| (error.outline, error.option)), Some((Some(0), None)));
+
+    let role_list = RoleList::from_json(&serde_json::json!([[{"role": "jester"}], [{"roleSet": "town", "weight": 2}]]));
+    assert_eq!(role_list, Ok(RoleList(vec![
+        RoleOutline::new_exact(Role::Jester),
+        RoleOutline { options: vec1::vec1![RoleOutlineOption {
+            roles: RoleOutlineOptionRoles::RoleSet { role_set: RoleSet::Town },
+            weight: 2,
+            ..Default::default()
+        }]}
+    ])));
+}
+
+#[test]
+fn role_outline_option_deserialization_is_strict() {
+    assert!(serde_json::from_str::<RoleOutlineOption>(r#"{"roleSet": "notARoleSet"}"#).is_err());
+    assert!(serde_json::from_str::<RoleOutlineOption>(r#"{"role": "jester", "insiderGroups": ["notAGroup"]}"#).is_err());
+    assert!(serde_json::from_str::<RoleOutlineOption>(r#"{}"#).is_err());
+    assert_eq!(
+        serde_json::from_str::<RoleOutlineOption>(r#"{"role": "jester"}"#).ok(),
+        Some(RoleOutlineOption { roles: RoleOutlineOptionRoles::Role { role: Role::Jester }, ..Default::default() })
+    );
+}
+
+#[test]
+fn role_set_grave_killers_uses_chosen_custom_role_sets() {
+    kit::scenario!(game in Night 2 where
+        mafioso: Mafioso,
+        vigilante: Vigilante,
+        townie: Detective
+    );
+    game.settings.custom_role_sets = vec![CustomRoleSet { name: "Gunners".to_string(), roles: vec![Role::Vigilante] }];
+    Modifiers::set_modifier(&mut game, ModifierState::RoleSetGraveKillers(RoleSetGraveKillers {
+        custom_role_sets: vec!["Gunners".to_string()]
+    }));
+
+    assert!(mafioso.send_ability_input_player_list_typical(townie));
+    assert!(vigilante.send_ability_input_player_list_typical(townie));
+    game.next_phase();
+
+    let GraveInformation::Normal { death_cause, .. } = &game.graves.first().unwrap().information else {
+        panic!("grave should not be obscured");
+    };
+    assert_eq!(*death_cause, GraveDeathCause::Killers(vec![
+        GraveKiller::RoleSet(RoleSet::Mafia),
+        GraveKiller::CustomRoleSet("Gunners".to_string())
+    ]));
+}
+
+#[test]
+fn custom_role_sets_generate_their_roles_and_stay_up_to_date() {
+    let mut settings = Settings {
+        role_list: RoleList(vec![outline_of(RoleOutlineOptionRoles::CustomRoleSet { custom_role_set: "Town Power".to_string() })]),
+        enabled_roles: Role::values().into_iter().collect(),
+        custom_role_sets: vec![CustomRoleSet { name: "Town Power".to_string(), roles: vec![Role::Jailor, Role::Mayor] }],
+        ..Default::default()
+    };
+
+    for _ in 0..10 {
+        let assignments = settings.role_list.create_random_role_assignments(&settings).expect("role list should generate");
+        assert!(matches!(assignments[0].role, Role::Jailor | Role::Mayor));
+    }
+
+    settings.custom_role_sets[0].roles = vec![Role::Veteran];
+    let assignments = settings.role_list.create_random_role_assignments(&settings).expect("role list should generate");
+    assert_eq!(assignments[0].role, Role::Veteran);
+
+    settings.enabled_roles = vec![Role::Jailor].into_iter().collect();
+    assert_eq!(
+        settings.check_custom_role_sets(&settings.custom_role_sets),
+        Err(CustomRoleSetError::RoleNotEnabled { set: 0, role: Role::Veteran })
+    );
+}
+
+#[test]
+fn disabling_roles_removes_them_from_custom_role_sets() {
+    let mut settings = Settings {
+        role_list: RoleList(vec![outline_of(RoleOutlineOptionRoles::CustomRoleSet { custom_role_set: "Gunners".to_string() })]),
+        enabled_roles: vec![Role::Jailor, Role::Mayor, Role::Vigilante].into_iter().collect(),
+        custom_role_sets: vec![
+            CustomRoleSet { name: "Town Power".to_string(), roles: vec![Role::Jailor, Role::Mayor] },
+            CustomRoleSet { name: "Gunners".to_string(), roles: vec![Role::Vigilante] },
+        ],
+        ..Default::default()
+    };
+
+    settings.enabled_roles = vec![Role::Jailor].into_iter().collect();
+    settings.remove_disabled_roles_from_custom_role_sets();
+
+    assert_eq!(settings.custom_role_sets, vec![CustomRoleSet { name: "Town Power".to_string(), roles: vec![Role::Jailor] }]);
+    assert!(settings.has_valid_parameters());
+    assert_eq!(
+        settings.role_list.create_random_role_assignments(&settings).err(),
+        Some(RoleListGenerationError::OutlineHasNoRoles { outline: 0 })
+    );
+}
+
+#[test]
+fn custom_insider_groups_reveal_roles_and_get_their_own_chat() {
+    let masons = |role: Role| RoleOutline { options: vec1::vec1![RoleOutlineOption {
+        roles: RoleOutlineOptionRoles::Role { role },
+        insider_groups: RoleOutlineOptionInsiderGroups::Custom {
+            insider_groups: vec![InsiderGroupID::Custom(0), InsiderGroupID::Custom(5)].into_iter().collect()
+        },
+        ..Default::default()
+    }]};
+    let settings = Settings {
+        role_list: RoleList(vec![masons(Role::Detective), masons(Role::Doctor), RoleOutline::new_exact(Role::Mafioso)]),
+        enabled_roles: Role::values().into_iter().collect(),
+        custom_insider_groups: vec![CustomInsiderGroup { name: "Masons".to_string() }],
+        ..Default::default()
+    };
+    let mut game = mafia_server::game::test::mock_game(settings, 3).expect("game should start");
+    let mut game = kit::game::TestGame::new(&mut game);
+
+    let player_with = |game: &mafia_server::game::Game, role: Role| PlayerReference::all_players(game)
+        .find(|player| player.role(game) == role)
+        .expect("every role should be given out");
+    let detective = player_with(&game, Role::Detective);
+    let doctor = player_with(&game, Role::Doctor);
+    let mafioso = player_with(&game, Role::Mafioso);
+
+    // Custom(5) doesn't exist, so it's ignored
+    assert_eq!(InsiderGroupID::all_insider_groups_with_player(&game, detective), vec![InsiderGroupID::Custom(0)].into_iter().collect());
+    assert!(InsiderGroupID::in_same_revealed_group(&game, detective, doctor));
+    assert!(!InsiderGroupID::in_same_revealed_group(&game, detective, mafioso));
+
+    assert!(detective.get_current_receive_chat_groups(&game).contains(&ChatGroup::Custom(0)));
+    assert!(!mafioso.get_current_receive_chat_groups(&game).contains(&ChatGroup::Custom(0)));
+
+    game.skip_to(PhaseType::Night, 1);
+    assert!(doctor.get_current_send_chat_groups(&game).contains(&ChatGroup::Custom(0)));
+    assert!(!mafioso.get_current_send_chat_groups(&game).contains(&ChatGroup::Custom(0)));
+    assert!(!ChatGroup::Custom(0).all_players_in_group(&game).contains(&mafioso));
+}
+
+#[test]
+fn custom_insider_groups_need_unique_names() {
+    let group = |name: &str| CustomInsiderGroup { name: name.to_string() };
+
+    assert_eq!(Settings::check_custom_insider_groups(&[group("Masons"), group("Second Mafia")]), Ok(()));
+    assert_eq!(
+        Settings::check_custom_insider_groups(&[group("Masons"), group("Masons")]),
+        Err(CustomInsiderGroupError::DuplicateName { group: 1 })
+    );
+    assert_eq!(Settings::check_custom_insider_groups(&[group(" ")]), Err(CustomInsiderGroupError::InvalidName { group: 0 }));
+}
+
+#[test]
+fn custom_insider_groups_in_use_can_be_renamed_but_not_removed_or_moved() {
+    let group = |name: &str| CustomInsiderGroup { name: name.to_string() };
+    let settings = Settings {
+        role_list: RoleList(vec![RoleOutline { options: vec1::vec1![RoleOutlineOption {
+            roles: RoleOutlineOptionRoles::Role { role: Role::Detective },
+            insider_groups: RoleOutlineOptionInsiderGroups::Custom {
+                insider_groups: vec![InsiderGroupID::Custom(1)].into_iter().collect()
+            },
+            ..Default::default()
+        }]}]),
+        custom_insider_groups: vec![group("Masons"), group("Cult")],
+        ..Default::default()
+    };
+
+    assert_eq!(settings.check_custom_insider_group_changes(&[group("Masons"), group("Coven")]), Ok(()));
+    assert_eq!(settings.check_custom_insider_group_changes(&[group("Lovers"), group("Cult"), group("Masons")]), Ok(()));
+    assert_eq!(
+        settings.check_custom_insider_group_changes(&[group("Masons")]),
+        Err(CustomInsiderGroupError::GroupInUse { group: 1 })
+    );
+    assert_eq!(
+        settings.check_custom_insider_group_changes(&[group("Cult"), group("Masons")]),
+        Err(CustomInsiderGroupError::GroupInUse { group: 1 })
+    );
+}
+
+#[test]
+fn custom_factions_keep_the_game_running_until_one_is_left() {
+    let faction = |role: Role, id: u8| RoleOutline { options: vec1::vec1![RoleOutlineOption {
+        roles: RoleOutlineOptionRoles::Role { role },
+        win_condition: RoleOutlineOptionWinCondition::GameConclusionReached {
+            win_if_any: vec![GameConclusion::Custom(id)].into_iter().collect()
+        },
+        ..Default::default()
+    }]};
+    let team = |name: &str| CustomFaction { name: name.to_string(), keeps_game_running: vec![Role::Vigilante] };
+    let settings = Settings {
+        role_list: RoleList(vec![faction(Role::Vigilante, 0), faction(Role::Doctor, 0), faction(Role::Vigilante, 1)]),
+        enabled_roles: Role::values().into_iter().collect(),
+        custom_factions: vec![team("Red"), team("Blue")],
+        ..Default::default()
+    };
+    let mut game = mafia_server::game::test::mock_game(settings, 3).expect("game should start");
+    let [red_vigilante, red_doctor, blue_vigilante] = [0, 1, 2].map(|index| PlayerReference::all_players(&game).nth(index).unwrap());
+
+    assert!(red_vigilante.keeps_game_running(&game));
+    assert!(!red_doctor.keeps_game_running(&game));
+    assert!(blue_vigilante.keeps_game_running(&game));
+    assert_eq!(GameConclusion::game_is_over(&game), None);
+
+    blue_vigilante.set_alive(&mut game, false);
+    assert_eq!(GameConclusion::game_is_over(&game), Some(GameConclusion::Custom(0)));
+}
+
+#[test]
+fn custom_factions_need_unique_names_and_roles() {
+    let faction = |name: &str, keeps_game_running: Vec<Role>| CustomFaction { name: name.to_string(), keeps_game_running };
+
+    assert_eq!(Settings::check_custom_factions(&[faction("Red", vec![Role::Vigilante]), faction("Blue", vec![])]), Ok(()));
+    assert_eq!(
+        Settings::check_custom_factions(&[faction("Red", vec![]), faction("Red", vec![])]),
+        Err(CustomFactionError::DuplicateName { faction: 1 })
+    );
+    assert_eq!(
+        Settings::check_custom_factions(&[faction("Red", vec![Role::Vigilante, Role::Vigilante])]),
+        Err(CustomFactionError::DuplicateRole { faction: 0, role: Role::Vigilante })
+    );
+}
+
+#[test]
+fn custom_factions_in_use_can_be_renamed_but_not_removed_or_moved() {
+    let faction = |name: &str| CustomFaction { name: name.to_string(), keeps_game_running: vec![] };
+    let settings = Settings {
+        role_list: RoleList(vec![RoleOutline { options: vec1::vec1![RoleOutlineOption {
+            roles: RoleOutlineOptionRoles::Role { role: Role::Vigilante },
+            win_condition: RoleOutlineOptionWinCondition::GameConclusionReached {
+                win_if_any: vec![GameConclusion::Custom(0)].into_iter().collect()
+            },
+            ..Default::default()
+        }]}]),
+        custom_factions: vec![faction("Red"), faction("Blue")],
+        ..Default::default()
+    };
+
+    assert_eq!(settings.check_custom_faction_changes(&[faction("Crimson")]), Ok(()));
+    assert_eq!(
+        settings.check_custom_faction_changes(&[faction("Blue"), faction("Red")]),
+        Err(CustomFactionError::FactionInUse { faction: 0 })
+    );
+    assert_eq!(settings.check_custom_faction_changes(&[]), Err(CustomFactionError::FactionInUse { faction: 0 }));
+}
+
+#[test]
+fn spectator_chat_is_only_seen_by_spectators() {
+    kit::scenario!(game in Night 1 where
+        zealot: Zealot,
+        townie: Detective
+    );
+    let spectator = game.add_spectator(SpectatorInitializeParameters { connection: ClientConnection::Disconnected, host: false });
+
+    game.add_message_to_chat_group(ChatGroup::Cult, ChatMessageVariant::CultKillsNext);
+    game.on_spectator_message(spectator, ToServerPacket::SendChatMessage { text: "hello".to_string(), block: false, reply_to: None, mentions: vec![] });
+
+    let spectator_message = ChatMessageVariant::Normal {
+        message_sender: MessageSender::Spectator { spectator },
+        text: "hello".to_string(),
+        block: false,
+        reply_to: None,
+        mentions: vec![]
+    };
+    assert!(game.spectator_chat_messages.iter().any(|message| message.variant == spectator_message && message.chat_group == Some(ChatGroup::Spectator)));
+    assert!(game.spectator_chat_messages.iter().all(|message| message.chat_group != Some(ChatGroup::Cult)));
+    assert_not_contains!(zealot.get_messages(), spectator_message);
+    assert_not_contains!(townie.get_messages(), spectator_message);
+}
+
+#[test]
+fn omniscient_spectators_see_every_chat_group() {
+    kit::scenario!(game in Night 1 where
+        zealot: Zealot,
+        townie: Detective
+    );
+    game.settings.spectator_settings.omniscient_view = true;
+
+    game.add_message_to_chat_group(ChatGroup::Cult, ChatMessageVariant::CultKillsNext);
+
+    assert!(game.spectator_chat_messages.iter().any(|message|
+        message.variant == ChatMessageVariant::CultKillsNext && message.chat_group == Some(ChatGroup::Cult)
+    ));
+    assert_contains!(zealot.get_messages(), ChatMessageVariant::CultKillsNext);
+    assert_not_contains!(townie.get_messages(), ChatMessageVariant::CultKillsNext);
+}
+
+#[test]
+fn whisper_rules_come_from_roles_and_modifiers() {
+    kit::scenario!(game in Discussion 2 where
+        informant: Informant,
+        mayor: Mayor,
+        townie: Detective,
+        mafioso: Mafioso
+    );
+
+    townie.send_whisper(mafioso, "hello");
+    let whisper = ChatMessageVariant::Whisper { from_player_index: townie.index(), to_player_index: mafioso.index(), text: "hello".to_string() };
+    assert_contains!(mafioso.get_messages(), whisper);
+    assert_contains!(informant.get_messages(), whisper);
+    assert_contains!(mayor.get_messages(), ChatMessageVariant::BroadcastWhisper { whisperer: townie.index(), whisperee: mafioso.index() });
+    assert_not_contains!(mayor.get_messages(), whisper);
+
+    mayor.set_role_state(RoleState::Mayor(Mayor { revealed: true }));
+    townie.send_whisper(mayor, "hi mayor");
+    assert_contains!(townie.get_messages(), ChatMessageVariant::InvalidWhisper);
+    assert!(mayor.get_messages().iter().all(|message| !matches!(message, ChatMessageVariant::Whisper { text, .. } if text == "hi mayor")));
+
+    Modifiers::set_modifier(&mut game, ModifierState::NoWhispers(Default::default()));
+    mafioso.send_whisper(townie, "secret");
+    assert!(townie.get_messages().iter().all(|message| !matches!(message, ChatMessageVariant::Whisper { text, .. } if text == "secret")));
+}
+
+#[test]
+fn whisper_settings_limit_whispers() {
+    kit::scenario!(game in Discussion 2 where
+        townie: Detective,
+        mafioso: Mafioso,
+        jester: Jester
+    );
+    game.settings.whisper_settings = WhisperSettings { cooldown_seconds: 30, max_length: 5, broadcast: false };
+
+    townie.send_whisper(mafioso, "hello there");
+    assert_contains!(mafioso.get_messages(), ChatMessageVariant::Whisper { from_player_index: townie.index(), to_player_index: mafioso.index(), text: "hello".to_string() });
+    assert!(jester.get_messages().iter().all(|message| !matches!(message, ChatMessageVariant::BroadcastWhisper { .. })));
+
+    let whisper_id = |player: PlayerReference, game: &mafia_server::game::Game| player.chat_messages(game).iter()
+        .find(|message| matches!(message.get_variant(), ChatMessageVariant::Whisper { .. }))
+        .map(|message| message.id);
+    let id = whisper_id(townie.player_ref(), &game).expect("the whisperer should see their whisper");
+    assert_eq!(whisper_id(mafioso.player_ref(), &game), Some(id));
+    game.delete_chat_message(id);
+    assert_eq!(whisper_id(townie.player_ref(), &game), None);
+    assert_eq!(whisper_id(mafioso.player_ref(), &game), None);
+
+    townie.send_whisper(jester, "again");
+    assert_contains!(townie.get_messages(), ChatMessageVariant::InvalidWhisper);
+    assert_not_contains!(jester.get_messages(), ChatMessageVariant::Whisper { from_player_index: townie.index(), to_player_index: jester.index(), text: "again".to_string() });
+
+    Whispers::tick(&mut game, Duration::from_secs(30));
+    townie.send_whisper(jester, "again");
+    assert_contains!(jester.get_messages(), ChatMessageVariant::Whisper { from_player_index: townie.index(), to_player_index: jester.index(), text: "again".to_string() });
+}
+
+#[test]
+fn game_history_keeps_real_roles_role_switches_and_trial_results(){
+    kit::scenario!(game in Night 1 where
+        mortician: Mortician,
+        townie: Detective,
+        jail: Jailor,
+        gf: Godfather
+    );
+    assert!(GameHistory::timeline(&game).is_empty());
+
+    mortician.send_ability_input_player_list_typical(townie);
+    game.skip_to(Nomination, 2);
+    jail.vote_for_player(Some(townie));
+    gf.vote_for_player(Some(townie));
+    mortician.vote_for_player(Some(townie));
+
+    game.skip_to(Judgement, 2);
+    jail.set_verdict(Verdict::Guilty);
+    game.skip_to(Night, 2);
+    jail.player_ref().set_role(&mut game, RoleState::Detective(Detective::default()));
+
+    let timeline = GameHistory::timeline(&game);
+    assert!(timeline.iter().any(|event| matches!(event.kind(),
+        GameHistoryEventKind::TrialResult { player_on_trial, voted_guilty: true, verdicts, .. }
+            if *player_on_trial == townie.player_ref() && verdicts.contains(&(jail.player_ref(), Verdict::Guilty))
+    )));
+    assert!(timeline.iter().any(|event| matches!(event.kind(),
+        GameHistoryEventKind::Grave { shown, original, role: Role::Detective }
+            if shown.information == GraveInformation::Obscured &&
+            matches!(original.information, GraveInformation::Normal { role: Role::Detective, .. })
+    )));
+    assert!(matches!(timeline.last().map(|event| event.kind()),
+        Some(GameHistoryEventKind::RoleSwitch { player, old: Role::Jailor, new: Role::Detective }) if *player == jail.player_ref()
+    ));
+
+    let json = GameHistory::to_json_string(&game).expect("history should serialize");
+    assert!(json.contains("\"type\":\"trialResult\""));
+}
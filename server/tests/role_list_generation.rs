@@ -0,0 +1,128 @@
+#[allow(unused)]
+mod kit;
+
+pub(crate) use kit::assert_contains;
+
+use mafia_server::game::{
+    role::Role,
+    role_list::{CustomRoleSet, RoleList, RoleListConstraint, RoleListConstraintError, RoleOutline, RoleOutlineOption, RoleOutlineOptionRoles, RoleSet},
+    role_list_generation::RoleListGenerationError,
+    settings::Settings
+};
+
+fn outline_of(roles: RoleOutlineOptionRoles) -> RoleOutline {
+    RoleOutline { options: vec1::vec1![RoleOutlineOption { roles, ..Default::default() }] }
+}
+
+#[test]
+fn role_list_generation_follows_constraints() {
+    let settings = Settings {
+        role_list: RoleList(vec![
+            outline_of(RoleOutlineOptionRoles::RoleSet { role_set: RoleSet::Any }),
+            outline_of(RoleOutlineOptionRoles::RoleSet { role_set: RoleSet::Any }),
+            outline_of(RoleOutlineOptionRoles::RoleSet { role_set: RoleSet::Any }),
+        ]),
+        role_list_constraints: vec![
+            RoleListConstraint::AtLeastOne { roles: RoleOutlineOptionRoles::Role { role: Role::Jester } },
+            RoleListConstraint::AtMost { roles: RoleOutlineOptionRoles::RoleSet { role_set: RoleSet::Mafia }, count: 0 },
+        ],
+        enabled_roles: Role::values().into_iter().collect(),
+        ..Default::default()
+    };
+
+    for _ in 0..20 {
+        let roles: Vec<Role> = settings.role_list.create_random_role_assignments(&settings)
+            .unwrap_or_else(|error| panic!("role list should generate, got {:?}", error))
+            .into_iter()
+            .map(|assignment| assignment.role)
+            .collect();
+        assert_eq!(roles.len(), 3);
+        assert_contains!(roles, Role::Jester);
+        assert!(roles.iter().all(|role| !RoleSet::Mafia.get_roles().contains(role)));
+    }
+}
+
+#[test]
+fn role_list_generation_reports_impossible_constraint() {
+    let mut settings = Settings {
+        role_list: RoleList(vec![
+            outline_of(RoleOutlineOptionRoles::RoleSet { role_set: RoleSet::Town }),
+            outline_of(RoleOutlineOptionRoles::RoleSet { role_set: RoleSet::Town }),
+        ]),
+        role_list_constraints: vec![
+            RoleListConstraint::AtMost { roles: RoleOutlineOptionRoles::Role { role: Role::Doctor }, count: 1 },
+            RoleListConstraint::AtLeastOne { roles: RoleOutlineOptionRoles::RoleSet { role_set: RoleSet::Mafia } },
+        ],
+        enabled_roles: Role::values().into_iter().collect(),
+        ..Default::default()
+    };
+    assert_eq!(
+        settings.role_list.create_random_role_assignments(&settings).err(),
+        Some(RoleListGenerationError::ConstraintCannotBeMet { constraint: 1 })
+    );
+
+    settings.role_list_constraints.clear();
+    settings.role_list = RoleList(vec![
+        outline_of(RoleOutlineOptionRoles::Role { role: Role::Deputy }),
+        outline_of(RoleOutlineOptionRoles::Role { role: Role::Deputy }),
+    ]);
+    assert_eq!(
+        settings.role_list.create_random_role_assignments(&settings).err(),
+        Some(RoleListGenerationError::MaximumCountExceeded)
+    );
+
+    settings.enabled_roles.remove(&Role::Deputy);
+    assert_eq!(
+        settings.role_list.create_random_role_assignments(&settings).err(),
+        Some(RoleListGenerationError::OutlineHasNoRoles { outline: 0 })
+    );
+}
+
+#[test]
+fn role_list_constraints_are_checked() {
+    let settings = Settings {
+        role_list: RoleList(vec![
+            outline_of(RoleOutlineOptionRoles::RoleSet { role_set: RoleSet::Town }),
+            outline_of(RoleOutlineOptionRoles::RoleSet { role_set: RoleSet::Town }),
+        ]),
+        custom_role_sets: vec![CustomRoleSet { name: "Gunners".to_string(), roles: vec![Role::Vigilante] }],
+        enabled_roles: Role::values().into_iter().collect(),
+        ..Default::default()
+    };
+    let at_most = |count: u8| RoleListConstraint::AtMost { roles: RoleOutlineOptionRoles::Role { role: Role::Doctor }, count };
+    let custom = |name: &str| RoleListConstraint::AtLeastOne {
+        roles: RoleOutlineOptionRoles::CustomRoleSet { custom_role_set: name.to_string() }
+    };
+
+    assert_eq!(settings.check_role_list_constraints(&[at_most(2), custom("Gunners")]), Ok(()));
+    assert_eq!(
+        settings.check_role_list_constraints(&[at_most(3)]),
+        Err(RoleListConstraintError::CountLargerThanRoleList { constraint: 0 })
+    );
+    assert_eq!(
+        settings.check_role_list_constraints(&[at_most(1), custom("Masons")]),
+        Err(RoleListConstraintError::UnknownCustomRoleSet { constraint: 1 })
+    );
+    assert_eq!(
+        settings.check_role_list_constraints(&vec![at_most(1); RoleListConstraint::MAX_COUNT + 1]),
+        Err(RoleListConstraintError::TooManyConstraints)
+    );
+}
+
+#[test]
+fn role_outline_option_with_zero_weight_never_generates() {
+    let settings = Settings {
+        enabled_roles: Role::values().into_iter().collect(),
+        ..Default::default()
+    };
+    let outline = RoleOutline { options: vec1::vec1![
+        RoleOutlineOption { roles: RoleOutlineOptionRoles::Role { role: Role::Doctor }, weight: 0, ..Default::default() },
+        RoleOutlineOption { roles: RoleOutlineOptionRoles::Role { role: Role::Detective }, weight: 5, ..Default::default() },
+    ]};
+
+    for _ in 0..20 {
+        let assignment = outline.get_random_role_assignments(&settings, &[])
+            .expect("detective should generate");
+        assert_eq!(assignment.role, Role::Detective);
+    }
+}
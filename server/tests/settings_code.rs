@@ -0,0 +1,95 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use mafia_server::{
+    game::{
+        components::insider_group::InsiderGroupID, game_conclusion::GameConclusion,
+        modifiers::ModifierType, role::Role,
+        role_list::{RoleList, RoleOutline, RoleOutlineOption, RoleOutlineOptionInsiderGroups, RoleOutlineOptionRoles, RoleOutlineOptionWinCondition, RoleSet},
+        settings::Settings
+    },
+    lobby::settings_code::{decode_settings, encode_settings, settings_from_json, SettingsCodeError, UnknownEntry, UnknownEntryKind},
+    vec_map::VecMap
+};
+
+#[test]
+fn settings_code_round_trips_custom_outline_options() {
+    let settings = Settings {
+        role_list: RoleList(vec![
+            RoleOutline { options: vec1::vec1![RoleOutlineOption {
+                roles: RoleOutlineOptionRoles::RoleSet { role_set: RoleSet::Neutral },
+                win_condition: RoleOutlineOptionWinCondition::GameConclusionReached {
+                    win_if_any: vec![GameConclusion::Mafia].into_iter().collect()
+                },
+                insider_groups: RoleOutlineOptionInsiderGroups::Custom {
+                    insider_groups: vec![InsiderGroupID::Mafia].into_iter().collect()
+                },
+                weight: 3
+            }]},
+            RoleOutline::new_exact(Role::Detective),
+        ]),
+        enabled_roles: Role::values().into_iter().collect(),
+        enabled_modifiers: VecMap::new_from_vec(vec![
+            (ModifierType::DeadCanChat, ModifierType::DeadCanChat.default_state())
+        ]),
+        ..Default::default()
+    };
+
+    let code = encode_settings(&settings);
+    assert!(code.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_')));
+
+    let decoded = decode_settings(&code).expect("code should decode");
+    assert_eq!(decoded.role_list, settings.role_list);
+    assert_eq!(decoded.enabled_roles, settings.enabled_roles);
+    assert!(decoded.enabled_modifiers.contains_key(&ModifierType::DeadCanChat));
+}
+
+#[test]
+fn settings_code_reports_unknown_entries() {
+    let json = r#"{
+        "enabledRoles": ["jester", "notARole"],
+        "enabledModifiers": [{"type": "notAModifier"}],
+        "roleList": [[{"roleSet": "town"}, {"role": "madeUp", "winIfAny": ["town", "nobody"]}]]
+    }"#;
+
+    assert_eq!(settings_from_json(json.as_bytes()).err(), Some(SettingsCodeError::UnknownEntries { entries: vec![
+        UnknownEntry { path: "enabledRoles[1]".to_string(), kind: UnknownEntryKind::Role, value: "notARole".to_string() },
+        UnknownEntry { path: "enabledModifiers[0].type".to_string(), kind: UnknownEntryKind::Modifier, value: "notAModifier".to_string() },
+        UnknownEntry { path: "roleList[0][1].role".to_string(), kind: UnknownEntryKind::Role, value: "madeUp".to_string() },
+        UnknownEntry { path: "roleList[0][1].winIfAny[1]".to_string(), kind: UnknownEntryKind::GameConclusion, value: "nobody".to_string() },
+    ]}));
+}
+
+#[test]
+fn settings_code_reports_unknown_roles_in_custom_factions() {
+    let json = r#"{
+        "customFactions": [{"name": "Red", "keepsGameRunning": ["vigilante", "notARole"]}]
+    }"#;
+
+    assert_eq!(settings_from_json(json.as_bytes()).err(), Some(SettingsCodeError::UnknownEntries { entries: vec![
+        UnknownEntry { path: "customFactions[0].keepsGameRunning[1]".to_string(), kind: UnknownEntryKind::Role, value: "notARole".to_string() },
+    ]}));
+}
+
+#[test]
+fn settings_code_rejects_other_versions_and_garbage() {
+    let code = encode_settings(&Settings::default());
+    let (_, data) = code.split_once('.').unwrap();
+
+    assert_eq!(decode_settings(&format!("200.{data}")).err(), Some(SettingsCodeError::UnsupportedVersion));
+    assert_eq!(decode_settings("1.!!!").err(), Some(SettingsCodeError::InvalidCode));
+    assert_eq!(decode_settings("nonsense").err(), Some(SettingsCodeError::InvalidCode));
+}
+
+#[test]
+fn settings_code_is_compressed_and_still_reads_uncompressed_codes() {
+    let settings = Settings {
+        enabled_roles: Role::values().into_iter().collect(),
+        ..Default::default()
+    };
+    let json = serde_json::to_string(&settings).unwrap();
+
+    let uncompressed_code = format!("1.{}", URL_SAFE_NO_PAD.encode(json.as_bytes()));
+    let code = encode_settings(&settings);
+    assert!(code.len() * 3 < uncompressed_code.len(), "code should be compressed, got {} characters", code.len());
+
+    assert_eq!(decode_settings(&uncompressed_code).expect("version 1 code should decode").enabled_roles, settings.enabled_roles);
+}
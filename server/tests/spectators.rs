@@ -0,0 +1,154 @@
+use std::time::Duration;
+
+use mafia_server::{
+    client_connection::ClientConnection,
+    game::{
+        chat::{ChatGroup, ChatMessageVariant}, role::Role,
+        role_list::{RoleList, RoleOutline}, settings::Settings,
+        spectator::{spectator_pointer::SpectatorPointer, SpectatorInitializeParameters},
+        player::PlayerReference, test::mock_game, Game
+    },
+    packet::ToClientPacket,
+    websocket_connections::connection::Connection
+};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
+
+fn game() -> Game {
+    mock_game(Settings {
+        role_list: RoleList(vec![RoleOutline::new_exact(Role::Detective), RoleOutline::new_exact(Role::Mafioso)]),
+        enabled_roles: vec![Role::Detective, Role::Mafioso].into_iter().collect(),
+        ..Default::default()
+    }, 2).expect("game should start")
+}
+
+fn connection() -> (ClientConnection, UnboundedReceiver<ToClientPacket>) {
+    let (tx, rx) = unbounded_channel();
+    let connection = Connection::new(tx, "127.0.0.1:0".parse().unwrap());
+    (ClientConnection::Connected(connection.get_sender()), rx)
+}
+
+fn spectator(connection: ClientConnection) -> SpectatorInitializeParameters {
+    SpectatorInitializeParameters { connection, host: false }
+}
+
+/// Every chat message sent in AddChatMessages packets so far, as JSON
+fn received_chat_messages(receiver: &mut UnboundedReceiver<ToClientPacket>) -> Vec<serde_json::Value> {
+    let mut messages = Vec::new();
+    while let Ok(packet) = receiver.try_recv() {
+        if let ToClientPacket::AddChatMessages { chat_messages } = packet {
+            messages.extend(chat_messages.iter().map(|message| serde_json::to_value(message).unwrap()));
+        }
+    }
+    messages
+}
+
+#[test]
+fn spectator_ids_stay_the_same_when_others_leave() {
+    let mut game = game();
+    let (first_connection, mut first) = connection();
+    let (second_connection, _second) = connection();
+    let (third_connection, mut third) = connection();
+
+    let first_id = game.add_spectator(spectator(first_connection));
+    let second_id = game.add_spectator(spectator(second_connection));
+    let third_id = game.add_spectator(spectator(third_connection));
+    received_chat_messages(&mut first);
+    received_chat_messages(&mut third);
+
+    game.remove_spectator(second_id);
+    game.add_message_to_chat_group(ChatGroup::All, ChatMessageVariant::PhaseFastForwarded);
+    SpectatorPointer::new(third_id).send_chat_messages(&mut game);
+
+    assert_eq!(received_chat_messages(&mut third).len(), 1);
+    assert!(received_chat_messages(&mut first).is_empty());
+    assert!(SpectatorPointer::new(second_id).deref(&game).is_none());
+
+    let (fourth_connection, _fourth) = connection();
+    let fourth_id = game.add_spectator(spectator(fourth_connection));
+    assert!(![first_id, second_id, third_id].contains(&fourth_id));
+}
+
+#[test]
+fn late_spectators_get_the_whole_chat_history() {
+    let mut game = game();
+    game.add_message_to_chat_group(ChatGroup::All, ChatMessageVariant::PhaseFastForwarded);
+    game.add_message_to_chat_group(ChatGroup::Cult, ChatMessageVariant::CultKillsNext);
+    game.add_message_to_chat_group(ChatGroup::All, ChatMessageVariant::PhaseFastForwarded);
+
+    let (connection, mut receiver) = connection();
+    game.add_spectator(spectator(connection));
+
+    let messages = received_chat_messages(&mut receiver);
+    assert_eq!(messages.len(), game.spectator_chat_messages.len());
+    assert!(messages.iter().all(|message| message["chatGroup"] == "all"));
+    assert_eq!(messages.iter().filter(|message| message["variant"]["type"] == "phaseFastForwarded").count(), 2);
+}
+
+#[test]
+fn reconnecting_spectators_keep_their_id_and_catch_up_once() {
+    let mut game = game();
+    let (connection, _old_receiver) = connection();
+    let id = game.add_spectator(spectator(connection));
+    let spectator = SpectatorPointer::new(id);
+
+    spectator.lose_connection(&mut game);
+    assert!(spectator.could_reconnect(&game));
+    game.add_message_to_chat_group(ChatGroup::All, ChatMessageVariant::PhaseFastForwarded);
+
+    let (tx, mut receiver) = unbounded_channel();
+    spectator.connect(&mut game, Connection::new(tx, "127.0.0.1:0".parse().unwrap()).get_sender());
+
+    assert!(!spectator.could_reconnect(&game));
+    assert_eq!(received_chat_messages(&mut receiver).len(), game.spectator_chat_messages.len());
+}
+
+#[test]
+fn stream_delay_holds_back_packets_for_everyone_but_hosts() {
+    let mut game = game();
+    game.settings.spectator_settings.stream_delay_seconds = 30;
+    let (host_connection, mut host_receiver) = connection();
+    let (connection, mut receiver) = connection();
+    game.add_spectator(spectator(connection));
+    game.add_spectator(SpectatorInitializeParameters { connection: host_connection, host: true });
+
+    assert!(receiver.try_recv().is_err());
+    assert!(host_receiver.try_recv().is_ok());
+    while host_receiver.try_recv().is_ok() {}
+
+    game.add_message_to_chat_group(ChatGroup::All, ChatMessageVariant::PhaseFastForwarded);
+    game.tick(Duration::from_secs(29));
+    assert!(receiver.try_recv().is_err());
+    assert!(!received_chat_messages(&mut host_receiver).is_empty());
+
+    game.tick(Duration::from_secs(31));
+    let messages = received_chat_messages(&mut receiver);
+    assert!(messages.iter().any(|message| message["variant"]["type"] == "phaseFastForwarded"));
+}
+
+#[test]
+fn chat_message_ids_are_shared_and_deletes_reach_everyone_who_had_the_message() {
+    let mut game = game();
+    let (connection, mut receiver) = connection();
+    game.add_spectator(spectator(connection));
+    received_chat_messages(&mut receiver);
+
+    game.add_message_to_chat_group(ChatGroup::All, ChatMessageVariant::PhaseFastForwarded);
+    game.add_message_to_chat_group(ChatGroup::All, ChatMessageVariant::PhaseFastForwarded);
+    let [first, second] = [0, 1].map(|index| PlayerReference::new(&game, index).unwrap());
+
+    let last_two = |player: PlayerReference, game: &Game| player.chat_messages(game).iter().rev().take(2).map(|message| message.id).collect::<Vec<_>>();
+    let ids = last_two(first, &game);
+    assert_eq!(ids, last_two(second, &game));
+    assert!(ids[0] > ids[1]);
+
+    game.delete_chat_message(ids[0]);
+    assert!(first.chat_messages(&game).iter().all(|message| message.id != ids[0]));
+    assert!(game.spectator_chat_messages.iter().all(|message| message.id != ids[0]));
+    assert!(second.chat_messages(&game).iter().any(|message| message.id == ids[1]));
+
+    let mut deleted = false;
+    while let Ok(packet) = receiver.try_recv() {
+        deleted |= matches!(packet, ToClientPacket::ChatMessageDeleted { id } if id == ids[0]);
+    }
+    assert!(deleted);
+}
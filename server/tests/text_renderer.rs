@@ -0,0 +1,82 @@
+#[allow(unused)]
+mod kit;
+
+use mafia_server::{
+    game::{
+        chat::ChatMessageVariant,
+        grave::{Grave, GraveDeathCause, GraveInformation, GraveKiller, GravePhase},
+        phase::PhaseType,
+        role::{mafioso::Mafioso, villager::Villager, Role, RoleState},
+        role_list::RoleSet
+    },
+    lang::{serde_name, Lang, TextRenderer}
+};
+
+#[test]
+fn text_renderer_uses_the_locale_and_player_names() {
+    kit::scenario!(game in Discussion 2 where
+        townie: Villager,
+        mafioso: Mafioso
+    );
+    let townie_name = townie.player_ref().name(&game).clone();
+    let mafioso_name = mafioso.player_ref().name(&game).clone();
+    let renderer = TextRenderer::from_game(Lang::en_us(), &game);
+
+    assert_eq!(
+        renderer.chat_message_variant(&ChatMessageVariant::Whisper {
+            from_player_index: townie.index(), to_player_index: mafioso.index(), text: "hi @2".to_string()
+        }),
+        format!("{townie_name} whispered to {mafioso_name}: hi {mafioso_name}")
+    );
+    assert_eq!(
+        renderer.chat_message_variant(&ChatMessageVariant::TrialVerdict { player_on_trial: mafioso.index(), innocent: 1, guilty: 2 }),
+        format!("{mafioso_name} was voted Guilty with 1 innocent votes and 2 guilty votes.")
+    );
+    assert_eq!(
+        renderer.chat_message_variant(&ChatMessageVariant::YouDied),
+        Lang::en_us().translate("chatMessage.youDied", &[])
+    );
+
+    let grave = renderer.grave(&Grave {
+        player: townie.player_ref(),
+        died_phase: GravePhase::Night,
+        day_number: 1,
+        information: GraveInformation::Normal {
+            role: Role::Villager,
+            will: "@1 is town".to_string(),
+            death_cause: GraveDeathCause::Killers(vec![GraveKiller::Role(Role::Mafioso), GraveKiller::RoleSet(RoleSet::Mafia)]),
+            death_notes: vec![],
+        },
+    });
+    assert!(grave.contains(&format!("{townie_name} (Villager)")));
+    assert!(grave.contains("Killed by Mafioso, Syndicate."));
+    assert!(grave.contains(&format!("{townie_name} is town")));
+
+    let lang = Lang::from_json(r#"{"chatMessage.broadcastWhisper": "\\0 -> \\1"}"#).expect("lang should parse");
+    let renderer = TextRenderer::new(&lang, vec!["A".to_string()]);
+    assert_eq!(
+        renderer.chat_message_variant(&ChatMessageVariant::BroadcastWhisper { whisperer: 0, whisperee: 1 }),
+        "A -> @2"
+    );
+    assert_eq!(renderer.chat_message_variant(&ChatMessageVariant::YouDied), "chatMessage.youDied");
+}
+
+#[test]
+fn every_role_has_a_name_in_the_english_locale() {
+    for role in Role::values() {
+        assert!(
+            Lang::en_us().translate_checked(&format!("role.{}.name", serde_name(&role)), &[]).is_some(),
+            "{role:?} has no name"
+        );
+    }
+}
+
+#[test]
+fn mentions_inside_player_names_are_not_replaced_again() {
+    let renderer = TextRenderer::new(Lang::en_us(), vec!["@2 fan".to_string(), "B".to_string()]);
+
+    assert_eq!(
+        renderer.chat_message_variant(&ChatMessageVariant::ReporterReport { report: "@1 and @2, not @3 or @21".to_string() }),
+        Lang::en_us().translate("chatMessage.reporterReport", &["@2 fan and B, not @3 or B1".to_string()])
+    );
+}
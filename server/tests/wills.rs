@@ -0,0 +1,76 @@
+#[allow(unused)]
+mod kit;
+
+use mafia_server::{
+    game::{
+        ability_input::{ability_selection::AbilitySelection, AbilityInput, ControllerID},
+        components::will_history::{WillAuthor, WillHistory},
+        grave::GraveInformation,
+        phase::PhaseType,
+        role::{forger::Forger, mafioso::Mafioso, villager::Villager, Role, RoleState},
+        will_template::{PlayerClaim, WillTemplate}
+    },
+    packet::ToServerPacket
+};
+
+#[test]
+fn will_templates_are_written_out_and_every_will_version_is_kept() {
+    kit::scenario!(game in Night 2 where
+        townie: Villager,
+        forger: Forger,
+        mafioso: Mafioso
+    );
+    game.on_client_message(townie.index(), ToServerPacket::SaveWillTemplate { template: WillTemplate::RoleClaim {
+        role: Some(Role::Villager),
+        nights: vec!["nothing".to_string(), "  watched\n @2 ".to_string()],
+        notes: String::new()
+    }});
+    assert_eq!(townie.player_ref().will(&game), "Role: Villager\nNight 1: nothing\nNight 2: watched @2");
+
+    let template = WillTemplate::PlayerClaims { claims: vec![
+        PlayerClaim { player: forger.index(), role: Some(Role::Doctor), note: "sus".to_string() },
+        PlayerClaim { player: 200, role: None, note: String::new() },
+    ]};
+    assert_eq!(template.to_will(&game), "@2 - Doctor sus");
+
+    forger.send_ability_input_player_list_typical(townie);
+    forger.send_ability_input(AbilityInput::new(
+        ControllerID::role(forger.player_ref(), Role::Forger, 1),
+        AbilitySelection::new_role_option(Some(Role::Jester))
+    ));
+    forger.send_ability_input(AbilityInput::new(
+        ControllerID::role(forger.player_ref(), Role::Forger, 2),
+        AbilitySelection::new_string("I was the jester".to_string())
+    ));
+    mafioso.send_ability_input_player_list_typical(townie);
+    game.next_phase();
+
+    assert!(!townie.alive());
+    assert!(matches!(&game.graves[0].information,
+        GraveInformation::Normal { role: Role::Jester, will, .. } if will == "I was the jester"
+    ));
+
+    let authors: Vec<WillAuthor> = WillHistory::versions_of(&game, townie.player_ref())
+        .map(|version| version.author.clone())
+        .collect();
+    assert_eq!(authors, vec![WillAuthor::Player, WillAuthor::Role { player: forger.player_ref(), role: Role::Forger }]);
+    assert!(WillHistory::versions_of(&game, forger.player_ref()).next().is_none());
+}
+
+#[test]
+fn wills_and_will_history_are_capped() {
+    kit::scenario!(game in Night 2 where
+        townie: Villager,
+        mafioso: Mafioso
+    );
+    for index in 0..WillHistory::MAX_VERSIONS_PER_PLAYER + 10 {
+        game.on_client_message(townie.index(), ToServerPacket::SaveWill { will: index.to_string() });
+    }
+    let wills: Vec<String> = WillHistory::versions_of(&game, townie.player_ref()).map(|version| version.will.clone()).collect();
+    assert_eq!(wills.len(), WillHistory::MAX_VERSIONS_PER_PLAYER);
+    assert_eq!(wills.first(), Some(&"10".to_string()));
+    assert_eq!(wills.last(), Some(&(WillHistory::MAX_VERSIONS_PER_PLAYER + 9).to_string()));
+
+    game.on_client_message(mafioso.index(), ToServerPacket::SaveWill { will: "a".repeat(WillHistory::MAX_WILL_LENGTH + 100) });
+    assert_eq!(mafioso.player_ref().will(&game).chars().count(), WillHistory::MAX_WILL_LENGTH);
+}